@@ -0,0 +1,158 @@
+use super::{collect_palette, compute_minimal_cubeset, CubeSet};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct LimitViolation {
+    draw_index: usize,
+    colour: String,
+    count: u32,
+    limit: u32,
+}
+
+impl fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "draw {} has {} {}, limit is {}",
+            self.draw_index + 1,
+            self.count,
+            self.colour,
+            self.limit
+        )
+    }
+}
+
+pub fn explain_game(cube_set_vec: &[CubeSet], limits: &CubeSet) -> Vec<LimitViolation> {
+    let mut violations = Vec::new();
+    for (draw_index, cube_set) in cube_set_vec.iter().enumerate() {
+        let mut colours: Vec<&String> = cube_set.counts.keys().collect();
+        colours.sort();
+        for colour in colours {
+            let count = cube_set.count(colour);
+            let limit = limits.count(colour);
+            if count > limit {
+                violations.push(LimitViolation {
+                    draw_index,
+                    colour: colour.clone(),
+                    count,
+                    limit,
+                });
+            }
+        }
+    }
+    violations
+}
+
+pub fn explain_impossible_games(
+    games: &[(u32, Vec<CubeSet>)],
+    limits: &CubeSet,
+) -> Vec<(u32, Vec<LimitViolation>)> {
+    games
+        .iter()
+        .map(|(game_index, cube_set_vec)| (*game_index, explain_game(cube_set_vec, limits)))
+        .filter(|(_, violations)| !violations.is_empty())
+        .collect()
+}
+
+// The per-colour maximum over the minimal sets of the selected games
+pub fn smallest_limits_for(
+    games: &[(u32, Vec<CubeSet>)],
+    game_indices: &[u32],
+) -> Result<CubeSet, String> {
+    let mut selected_draws = Vec::new();
+    for game_index in game_indices {
+        let (_, cube_set_vec) = games
+            .iter()
+            .find(|(index, _)| index == game_index)
+            .ok_or(format!("Game {game_index} not found"))?;
+        selected_draws.push(compute_minimal_cubeset(cube_set_vec));
+    }
+    Ok(compute_minimal_cubeset(&selected_draws))
+}
+
+// Exhaustive over the distinct per-colour requirements, so this is exponential in the palette
+// size; the last colour always takes whatever budget the others leave over.
+pub fn largest_feasible_games(games: &[(u32, Vec<CubeSet>)], budget: u32) -> (CubeSet, Vec<u32>) {
+    let palette = collect_palette(games);
+    let minimal_cubesets: Vec<(u32, CubeSet)> = games
+        .iter()
+        .map(|(game_index, cube_set_vec)| (*game_index, compute_minimal_cubeset(cube_set_vec)))
+        .collect();
+    let candidates: Vec<Vec<u32>> = palette
+        .iter()
+        .map(|colour| {
+            let mut values: Vec<u32> = minimal_cubesets
+                .iter()
+                .map(|(_, minimal_cubeset)| minimal_cubeset.count(colour))
+                .chain([0])
+                .collect();
+            values.sort();
+            values.dedup();
+            values
+        })
+        .collect();
+
+    let mut best_games = Vec::new();
+    let mut limit_values = vec![0; palette.len()];
+    search_limits(
+        &palette,
+        &candidates,
+        &minimal_cubesets,
+        budget,
+        &mut limit_values,
+        &mut best_games,
+    );
+
+    let limits = smallest_limits_for(games, &best_games).unwrap_or_default();
+    (limits, best_games)
+}
+
+fn search_limits(
+    palette: &[String],
+    candidates: &[Vec<u32>],
+    minimal_cubesets: &[(u32, CubeSet)],
+    remaining_budget: u32,
+    limit_values: &mut Vec<u32>,
+    best_games: &mut Vec<u32>,
+) {
+    let depth = palette.len() - candidates.len();
+    match candidates {
+        [] => {
+            let limits = CubeSet {
+                counts: palette
+                    .iter()
+                    .cloned()
+                    .zip(limit_values.iter().copied())
+                    .collect(),
+            };
+            let feasible_games: Vec<u32> = minimal_cubesets
+                .iter()
+                .filter(|(_, minimal_cubeset)| minimal_cubeset.fits_within(&limits))
+                .map(|(game_index, _)| *game_index)
+                .collect();
+            if feasible_games.len() > best_games.len() {
+                *best_games = feasible_games;
+            }
+        }
+        [_] => {
+            limit_values[depth] = remaining_budget;
+            search_limits(palette, &[], minimal_cubesets, 0, limit_values, best_games);
+        }
+        [colour_candidates, rest @ ..] => {
+            for &value in colour_candidates
+                .iter()
+                .take_while(|&&v| v <= remaining_budget)
+            {
+                limit_values[depth] = value;
+                search_limits(
+                    palette,
+                    rest,
+                    minimal_cubesets,
+                    remaining_budget - value,
+                    limit_values,
+                    best_games,
+                );
+            }
+        }
+    }
+}
@@ -1,40 +1,47 @@
+mod game_analytics;
+
 use regex::Regex;
-use std::fs;
+use std::{collections::HashMap, env, fmt, fs};
 
-#[derive(Debug)]
-struct CubeSet {
-    red: u32,
-    green: u32,
-    blue: u32,
+#[derive(Debug, Default)]
+pub struct CubeSet {
+    counts: HashMap<String, u32>,
 }
 
-fn process_cubes(cubes: &str) -> CubeSet {
-    let red_re = Regex::new(r"(\d+) red").unwrap();
-    let red_count: u32 = if let Some(red_cap) = red_re.captures(cubes) {
-        red_cap.get(1).unwrap().as_str().parse().unwrap()
-    } else {
-        0
-    };
+impl fmt::Display for CubeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut colours: Vec<&String> = self.counts.keys().collect();
+        colours.sort();
+        let cubes: Vec<String> = colours
+            .into_iter()
+            .map(|colour| format!("{} {colour}", self.count(colour)))
+            .collect();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
 
-    let green_re = Regex::new(r"(\d+) green").unwrap();
-    let green_count: u32 = if let Some(green_cap) = green_re.captures(cubes) {
-        green_cap.get(1).unwrap().as_str().parse().unwrap()
-    } else {
-        0
-    };
+impl CubeSet {
+    pub fn count(&self, colour: &str) -> u32 {
+        self.counts.get(colour).copied().unwrap_or(0)
+    }
 
-    let blue_re = Regex::new(r"(\d+) blue").unwrap();
-    let blue_count: u32 = if let Some(blue_cap) = blue_re.captures(cubes) {
-        blue_cap.get(1).unwrap().as_str().parse().unwrap()
-    } else {
-        0
-    };
+    // Colours missing from the limits are treated as a limit of zero
+    pub fn fits_within(&self, limits: &CubeSet) -> bool {
+        self.counts
+            .iter()
+            .all(|(colour, &count)| count <= limits.count(colour))
+    }
+}
 
-    CubeSet {
-        red: red_count,
-        green: green_count,
-        blue: blue_count,
+fn process_cubes(cubes: &str) -> CubeSet {
+    let cube_re = Regex::new(r"(\d+) ([a-z]+)").unwrap();
+    let mut cube_set = CubeSet::default();
+    for cube_cap in cube_re.captures_iter(cubes) {
+        let count: u32 = cube_cap.get(1).unwrap().as_str().parse().unwrap();
+        let colour = cube_cap.get(2).unwrap().as_str().to_string();
+        *cube_set.counts.entry(colour).or_insert(0) += count;
     }
+    cube_set
 }
 
 fn process_game(input_line: &str) -> (u32, Vec<CubeSet>) {
@@ -45,41 +52,70 @@ fn process_game(input_line: &str) -> (u32, Vec<CubeSet>) {
     (game_index, game_data.map(process_cubes).collect())
 }
 
-fn part_one(games: &Vec<(u32, Vec<CubeSet>)>) -> u32 {
+// Limits are given as `<colour>=<count>` arguments, e.g. `red=12 green=13 blue=14`
+fn parse_bag_limits<I: Iterator<Item = String>>(args: I) -> Result<CubeSet, String> {
+    let mut limits = CubeSet::default();
+    for arg in args {
+        let (colour, count) = arg
+            .split_once('=')
+            .ok_or(format!("Expected <colour>=<count>, got: {arg}"))?;
+        let count: u32 = count
+            .parse()
+            .map_err(|err| format!("Invalid count for {colour}: {err}"))?;
+        limits.counts.insert(colour.to_string(), count);
+    }
+    if limits.counts.is_empty() {
+        limits.counts = HashMap::from([
+            (String::from("red"), 12),
+            (String::from("green"), 13),
+            (String::from("blue"), 14),
+        ]);
+    }
+    Ok(limits)
+}
+
+fn part_one(games: &[(u32, Vec<CubeSet>)], limits: &CubeSet) -> u32 {
     let possible_games = games.iter().filter(|(_, cube_set_vec)| {
         cube_set_vec
-            .into_iter()
-            .all(|cube_set| cube_set.red <= 12 && cube_set.green <= 13 && cube_set.blue <= 14)
+            .iter()
+            .all(|cube_set| cube_set.fits_within(limits))
     });
     possible_games.map(|(game_index, _)| game_index).sum()
 }
 
-fn compute_minimal_cubeset(cube_set_vec: &Vec<CubeSet>) -> CubeSet {
-    CubeSet {
-        red: cube_set_vec
-            .iter()
-            .map(|cube_set| cube_set.red)
-            .max()
-            .unwrap(),
-        green: cube_set_vec
-            .iter()
-            .map(|cube_set| cube_set.green)
-            .max()
-            .unwrap(),
-        blue: cube_set_vec
-            .iter()
-            .map(|cube_set| cube_set.blue)
-            .max()
-            .unwrap(),
+pub fn compute_minimal_cubeset(cube_set_vec: &[CubeSet]) -> CubeSet {
+    let mut minimal_cubeset = CubeSet::default();
+    for cube_set in cube_set_vec {
+        for (colour, &count) in &cube_set.counts {
+            let minimal_count = minimal_cubeset.counts.entry(colour.clone()).or_insert(0);
+            *minimal_count = u32::max(*minimal_count, count);
+        }
     }
+    minimal_cubeset
 }
 
-fn part_two(games: &Vec<(u32, Vec<CubeSet>)>) -> u32 {
+// Every colour seen in any game, so a game missing a colour still has a power of zero
+pub fn collect_palette(games: &[(u32, Vec<CubeSet>)]) -> Vec<String> {
+    let mut palette: Vec<String> = games
+        .iter()
+        .flat_map(|(_, cube_set_vec)| cube_set_vec.iter())
+        .flat_map(|cube_set| cube_set.counts.keys().cloned())
+        .collect();
+    palette.sort();
+    palette.dedup();
+    palette
+}
+
+fn part_two(games: &[(u32, Vec<CubeSet>)]) -> u32 {
+    let palette = collect_palette(games);
     games
         .iter()
         .map(|(_, cube_set_vec)| {
             let minimal_cubeset = compute_minimal_cubeset(cube_set_vec);
-            minimal_cubeset.red * minimal_cubeset.green * minimal_cubeset.blue
+            palette
+                .iter()
+                .map(|colour| minimal_cubeset.count(colour))
+                .product::<u32>()
         })
         .sum()
 }
@@ -89,6 +125,56 @@ fn main() {
     let contents = fs::read_to_string(file_path).expect("Unable to read file: {file_path}");
     let games: Vec<(u32, Vec<CubeSet>)> = contents.lines().map(process_game).collect();
 
-    println!("Part One -- Possible Index Sum: {}", part_one(&games));
-    println!("Part Two -- Power Sum of Minimal Sets: {}", part_two(&games));
+    let (flag_args, limit_args): (Vec<String>, Vec<String>) =
+        env::args().skip(1).partition(|arg| arg.starts_with("--"));
+    let limits = match parse_bag_limits(limit_args.into_iter()) {
+        Ok(limits) => limits,
+        Err(err) => {
+            println!("Error parsing bag limits: {err}");
+            return;
+        }
+    };
+
+    println!(
+        "Part One -- Possible Index Sum: {}",
+        part_one(&games, &limits)
+    );
+    for (game_index, violations) in game_analytics::explain_impossible_games(&games, &limits) {
+        for violation in violations {
+            println!("  Game {game_index} impossible: {violation}");
+        }
+    }
+    println!(
+        "Part Two -- Power Sum of Minimal Sets: {}",
+        part_two(&games)
+    );
+
+    // Optional queries: `--games=1,2,5` and `--budget=<total cubes>`
+    for flag in flag_args {
+        if let Some(game_list) = flag.strip_prefix("--games=") {
+            let game_indices: Result<Vec<u32>, _> =
+                game_list.split(',').map(|index| index.parse()).collect();
+            match game_indices
+                .map_err(|err| format!("Invalid game list: {err}"))
+                .and_then(|game_indices| game_analytics::smallest_limits_for(&games, &game_indices))
+            {
+                Ok(limits) => println!("Smallest limits for games {game_list}: {limits}"),
+                Err(err) => println!("Error computing limits: {err}"),
+            }
+        } else if let Some(budget) = flag.strip_prefix("--budget=") {
+            match budget.parse::<u32>() {
+                Ok(budget) => {
+                    let (limits, game_indices) =
+                        game_analytics::largest_feasible_games(&games, budget);
+                    println!(
+                        "Largest feasible set under {budget} cubes: {} games {game_indices:?} with {limits}",
+                        game_indices.len()
+                    );
+                }
+                Err(err) => println!("Invalid budget: {err}"),
+            }
+        } else {
+            println!("Unknown flag: {flag}");
+        }
+    }
 }
@@ -1,7 +1,82 @@
+mod schematic_audit;
+
 use itertools::iproduct;
-use regex::Regex;
 use std::collections::HashMap;
-use std::fs;
+use std::{env, fs};
+
+#[derive(Debug, Clone, Copy)]
+enum Adjacency {
+    FourNeighbour,
+    EightNeighbour,
+    Radius(usize),
+}
+
+impl Adjacency {
+    fn radius(&self) -> usize {
+        match self {
+            Adjacency::FourNeighbour | Adjacency::EightNeighbour => 1,
+            Adjacency::Radius(radius) => *radius,
+        }
+    }
+
+    fn contains(&self, row_offset: usize, col_offset: usize) -> bool {
+        match self {
+            Adjacency::FourNeighbour => row_offset + col_offset <= 1,
+            Adjacency::EightNeighbour => usize::max(row_offset, col_offset) <= 1,
+            Adjacency::Radius(radius) => usize::max(row_offset, col_offset) <= *radius,
+        }
+    }
+
+    // `four`, `eight` or `radius:<n>`
+    fn from_arg(value: &str) -> Result<Self, String> {
+        match value.split_once(':') {
+            None if value == "four" => Ok(Adjacency::FourNeighbour),
+            None if value == "eight" => Ok(Adjacency::EightNeighbour),
+            Some(("radius", radius)) => radius
+                .parse()
+                .map(Adjacency::Radius)
+                .map_err(|err| format!("Invalid radius: {err}")),
+            _ => Err(format!("Unknown adjacency: {value}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Aggregation {
+    // Sum of part numbers adjacent to at least one matching symbol
+    PartSum,
+    // Count of part numbers adjacent to at least one matching symbol
+    PartCount,
+    // Sum over matching symbols of the product of their parts, if they have exactly this many
+    GearProduct(usize),
+    // Sum over matching symbols of the sum of their parts
+    GearSum,
+}
+
+impl Aggregation {
+    // `sum`, `count`, `gear-product:<n>` or `gear-sum`
+    fn from_arg(value: &str) -> Result<Self, String> {
+        match value.split_once(':') {
+            None if value == "sum" => Ok(Aggregation::PartSum),
+            None if value == "count" => Ok(Aggregation::PartCount),
+            None if value == "gear-sum" => Ok(Aggregation::GearSum),
+            Some(("gear-product", part_count)) => part_count
+                .parse()
+                .map(Aggregation::GearProduct)
+                .map_err(|err| format!("Invalid gear part count: {err}")),
+            _ => Err(format!("Unknown aggregation: {value}")),
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Aggregation::PartSum => "Sum of Part Numbers",
+            Aggregation::PartCount => "Count of Part Numbers",
+            Aggregation::GearProduct(_) => "SumProduct of Gear-Adjacent parts",
+            Aggregation::GearSum => "Sum of Gear-Adjacent parts",
+        }
+    }
+}
 
 #[derive(Debug)]
 struct PartNum {
@@ -11,113 +86,264 @@ struct PartNum {
 }
 
 impl PartNum {
-    fn adjacent_to_symbol(&self, symbol_map: &HashMap<(usize, usize), &str>) -> bool {
+    fn neighbour_cells(&self, adjacency: Adjacency) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let radius = adjacency.radius();
         iproduct!(
-            (self.loc.0.saturating_sub(1))..(self.loc.0 + 2),
-            (self.loc.1.saturating_sub(1))..(self.loc.1 + self.len + 1)
+            (self.loc.0.saturating_sub(radius))..(self.loc.0 + radius + 1),
+            (self.loc.1.saturating_sub(radius))..(self.loc.1 + self.len + radius)
         )
-        .any(|x| symbol_map.contains_key(&x))
+        .filter(move |&(row, col)| {
+            let row_offset = row.abs_diff(self.loc.0);
+            let col_offset = if col < self.loc.1 {
+                self.loc.1 - col
+            } else {
+                col.saturating_sub(self.loc.1 + self.len - 1)
+            };
+            adjacency.contains(row_offset, col_offset)
+        })
     }
 
-    fn all_adjacent_symbols(
+    fn adjacent_symbols(
         &self,
-        symbol_map: &HashMap<(usize, usize), &str>,
-        symbol_matcher: &dyn Fn(&str) -> bool,
+        symbol_grid: &SymbolGrid,
+        symbol_matcher: &dyn Fn(char) -> bool,
+        adjacency: Adjacency,
     ) -> Vec<(usize, usize)> {
-        iproduct!(
-            (self.loc.0.saturating_sub(1))..(self.loc.0 + 2),
-            (self.loc.1.saturating_sub(1))..(self.loc.1 + self.len + 1)
-        )
-        .filter(|x| {
-            if let Some(symbol) = symbol_map.get(&x) {
-                symbol_matcher(&symbol)
-            } else {
-                false
-            }
-        })
-        .collect()
+        self.neighbour_cells(adjacency)
+            .filter(|&(row, col)| {
+                if let Some(symbol) = symbol_grid.get(row, col) {
+                    symbol_matcher(symbol)
+                } else {
+                    false
+                }
+            })
+            .collect()
     }
 }
 
-fn part_one(part_num_vec: &Vec<PartNum>, symbol_map: &HashMap<(usize, usize), &str>) -> u32 {
-    part_num_vec
-        .iter()
-        .filter(|part_num| part_num.adjacent_to_symbol(symbol_map))
-        .map(|part_num| part_num.value)
-        .sum()
-}
-
-fn part_two(part_num_vec: &Vec<PartNum>, symbol_map: &HashMap<(usize, usize), &str>) -> u32 {
-    // Map from (location) -> (All part numbers adjacent), for each gear
-    let mut gears_to_part_numbers: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
-    // Populate gears_to_part_numbers
-    part_num_vec
-        .iter()
-        .flat_map(|part_num: &PartNum| {
-            part_num
-                .all_adjacent_symbols(symbol_map, &|s| s == "*")
-                .into_iter()
-                .map(|x| (x, part_num.value))
-        })
-        .for_each(|(loc, part_value)| {
-            if !gears_to_part_numbers.contains_key(&loc) {
-                gears_to_part_numbers.insert(loc, Vec::new());
-            };
-            gears_to_part_numbers
-                .get_mut(&loc)
-                .unwrap()
-                .push(part_value);
-        });
-    // Compute sum of products of gear part numbers
-    gears_to_part_numbers
-        .into_iter()
-        .map(|(_, part_value_vec)| {
-            if part_value_vec.len() == 2 {
-                part_value_vec[0] * part_value_vec[1]
-            } else {
-                0
+// Dense row-major grid of symbols, so neighbour lookups are plain indexing
+#[derive(Debug)]
+struct SymbolGrid {
+    width: usize,
+    height: usize,
+    cells: Vec<Option<char>>,
+}
+
+impl SymbolGrid {
+    fn get(&self, row: usize, col: usize) -> Option<char> {
+        if row < self.height && col < self.width {
+            self.cells[row * self.width + col]
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Schematic {
+    part_num_vec: Vec<PartNum>,
+    symbol_grid: SymbolGrid,
+    line_widths: Vec<usize>,
+}
+
+impl Schematic {
+    // Single pass over the file, collecting numbers and symbols together
+    fn parse(file_contents: &str) -> Self {
+        let height = file_contents.lines().count();
+        let width = file_contents
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut cells = vec![None; width * height];
+        let mut part_num_vec = Vec::new();
+        let mut line_widths = Vec::with_capacity(height);
+
+        for (line, line_index) in file_contents.lines().zip(0..) {
+            let mut current_num: Option<PartNum> = None;
+            for (col, c) in line.chars().enumerate() {
+                if let Some(digit) = c.to_digit(10) {
+                    let part_num = current_num.get_or_insert(PartNum {
+                        value: 0,
+                        loc: (line_index, col),
+                        len: 0,
+                    });
+                    part_num.value = part_num.value * 10 + digit;
+                    part_num.len += 1;
+                    continue;
+                }
+                if let Some(part_num) = current_num.take() {
+                    part_num_vec.push(part_num);
+                }
+                if c != '.' {
+                    cells[line_index * width + col] = Some(c);
+                }
             }
-        })
-        .sum()
+            if let Some(part_num) = current_num.take() {
+                part_num_vec.push(part_num);
+            }
+            line_widths.push(line.chars().count());
+        }
+
+        Schematic {
+            part_num_vec,
+            symbol_grid: SymbolGrid {
+                width,
+                height,
+                cells,
+            },
+            line_widths,
+        }
+    }
 }
 
-fn main() {
-    let digits_re = Regex::new(r"(\d+)").unwrap();
-    let symbol_re = Regex::new(r"[^\d.]").unwrap();
+struct SchematicEngine<'f> {
+    symbol_matcher: &'f dyn Fn(char) -> bool,
+    adjacency: Adjacency,
+    aggregation: Aggregation,
+}
+
+impl<'f> SchematicEngine<'f> {
+    fn new(
+        symbol_matcher: &'f dyn Fn(char) -> bool,
+        adjacency: Adjacency,
+        aggregation: Aggregation,
+    ) -> Self {
+        SchematicEngine {
+            symbol_matcher,
+            adjacency,
+            aggregation,
+        }
+    }
+
+    // Map from (location) -> (All part numbers adjacent), for each matching symbol
+    fn symbols_to_part_numbers(&self, schematic: &Schematic) -> HashMap<(usize, usize), Vec<u32>> {
+        let mut symbols_to_part_numbers: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
+        schematic
+            .part_num_vec
+            .iter()
+            .flat_map(|part_num| {
+                part_num
+                    .adjacent_symbols(&schematic.symbol_grid, self.symbol_matcher, self.adjacency)
+                    .into_iter()
+                    .map(|x| (x, part_num.value))
+            })
+            .for_each(|(loc, part_value)| {
+                symbols_to_part_numbers
+                    .entry(loc)
+                    .or_default()
+                    .push(part_value);
+            });
+        symbols_to_part_numbers
+    }
+
+    fn solve(&self, schematic: &Schematic) -> u32 {
+        let symbol_adjacent_parts = || {
+            schematic.part_num_vec.iter().filter(|part_num| {
+                !part_num
+                    .adjacent_symbols(&schematic.symbol_grid, self.symbol_matcher, self.adjacency)
+                    .is_empty()
+            })
+        };
+        match self.aggregation {
+            Aggregation::PartSum => symbol_adjacent_parts().map(|part_num| part_num.value).sum(),
+            Aggregation::PartCount => symbol_adjacent_parts().count() as u32,
+            Aggregation::GearProduct(part_count) => self
+                .symbols_to_part_numbers(schematic)
+                .into_values()
+                .filter(|part_value_vec| part_value_vec.len() == part_count)
+                .map(|part_value_vec| part_value_vec.iter().product::<u32>())
+                .sum(),
+            Aggregation::GearSum => self
+                .symbols_to_part_numbers(schematic)
+                .into_values()
+                .map(|part_value_vec| part_value_vec.iter().sum::<u32>())
+                .sum(),
+        }
+    }
+}
 
+fn part_one(schematic: &Schematic, options: &EngineOptions) -> u32 {
+    SchematicEngine::new(&|_| true, options.adjacency, options.part_one).solve(schematic)
+}
+
+fn part_two(schematic: &Schematic, options: &EngineOptions) -> u32 {
+    SchematicEngine::new(&|s| s == '*', options.adjacency, options.part_two).solve(schematic)
+}
+
+struct EngineOptions {
+    adjacency: Adjacency,
+    part_one: Aggregation,
+    part_two: Aggregation,
+    other_path: Option<String>,
+}
+
+// Defaults are the puzzle's rules: `--adjacency=eight --part-one=sum --part-two=gear-product:2`.
+// Any argument not starting with `--` is a second schematic to audit against.
+fn parse_engine_options<I: Iterator<Item = String>>(args: I) -> Result<EngineOptions, String> {
+    let mut options = EngineOptions {
+        adjacency: Adjacency::EightNeighbour,
+        part_one: Aggregation::PartSum,
+        part_two: Aggregation::GearProduct(2),
+        other_path: None,
+    };
+    for arg in args {
+        if !arg.starts_with("--") {
+            options.other_path = Some(arg);
+            continue;
+        }
+        let (flag, value) = arg
+            .split_once('=')
+            .ok_or(format!("Expected --<setting>=<value>, got: {arg}"))?;
+        match flag {
+            "--adjacency" => options.adjacency = Adjacency::from_arg(value)?,
+            "--part-one" => options.part_one = Aggregation::from_arg(value)?,
+            "--part-two" => options.part_two = Aggregation::from_arg(value)?,
+            _ => return Err(format!("Unknown setting: {flag}")),
+        }
+    }
+    Ok(options)
+}
+
+fn main() {
     let file_path = "input.txt";
     let file_contents = fs::read_to_string(file_path).expect("Unable to read file: {file_path}");
-    
-    let part_num_vec: Vec<PartNum> = file_contents
-        .lines()
-        .zip(0..)
-        .flat_map(|(line, line_index)| {
-            digits_re
-                .captures_iter(line)
-                .map(|c| c.get(0).unwrap())
-                .map(move |m| PartNum {
-                    value: m.as_str().parse().unwrap(),
-                    loc: (line_index, m.start()),
-                    len: m.len(),
-                })
-        })
-        .collect();
-    // println!("{:?}", part_num_vec);
-
-    let symbol_map: HashMap<(usize, usize), &str> = file_contents
-        .lines()
-        .zip(0..)
-        .flat_map(|(line, line_index)| {
-            symbol_re
-                .captures_iter(line)
-                .map(|c| c.get(0).unwrap())
-                .map(move |m| ((line_index, m.start()), m.as_str()))
-        })
-        .collect();
-    // println!("{:?}", symbol_map);
+    let schematic = Schematic::parse(&file_contents);
+
+    let options = match parse_engine_options(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            println!("Error parsing engine options: {err}");
+            return;
+        }
+    };
+
+    for issue in schematic_audit::validate(&schematic) {
+        println!("Warning -- {issue}");
+    }
+    // Optional second schematic to audit against: `day03 <edited_file>`
+    if let Some(other_path) = &options.other_path {
+        let other_contents = match fs::read_to_string(other_path) {
+            Ok(other_contents) => other_contents,
+            Err(err) => {
+                println!("Unable to read file {other_path}: {err}");
+                return;
+            }
+        };
+        let other_schematic = Schematic::parse(&other_contents);
+        for change in schematic_audit::diff(&schematic, &other_schematic) {
+            println!("Diff -- {change}");
+        }
+    }
 
-    let part_one_solution = part_one(&part_num_vec, &symbol_map);
-    println!("Part One -- Sum of Part Numbers: {part_one_solution}");
-    let part_two_solution = part_two(&part_num_vec, &symbol_map);
-    println!("Part Two -- SumProduct of Gear-Adjacent parts: {part_two_solution}");
+    let part_one_solution = part_one(&schematic, &options);
+    println!(
+        "Part One -- {}: {part_one_solution}",
+        options.part_one.description()
+    );
+    let part_two_solution = part_two(&schematic, &options);
+    println!(
+        "Part Two -- {}: {part_two_solution}",
+        options.part_two.description()
+    );
 }
@@ -0,0 +1,231 @@
+use super::{Adjacency, PartNum, Schematic};
+use std::{collections::HashMap, fmt};
+
+const GEAR: char = '*';
+
+#[derive(Debug)]
+pub enum SchematicIssue {
+    RaggedLine {
+        line_index: usize,
+        width: usize,
+        expected_width: usize,
+    },
+    // A number ending on the last column, directly above a row starting with a digit
+    WrappedNumber {
+        value: u32,
+        loc: (usize, usize),
+    },
+    NumberTouchingGears {
+        value: u32,
+        loc: (usize, usize),
+        gear_locs: Vec<(usize, usize)>,
+    },
+    OvercrowdedGear {
+        loc: (usize, usize),
+        part_values: Vec<u32>,
+    },
+}
+
+impl fmt::Display for SchematicIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchematicIssue::RaggedLine {
+                line_index,
+                width,
+                expected_width,
+            } => write!(
+                f,
+                "line {line_index} is {width} wide, expected {expected_width}"
+            ),
+            SchematicIssue::WrappedNumber { value, loc } => {
+                write!(f, "number {value} at {loc:?} may wrap onto the next line")
+            }
+            SchematicIssue::NumberTouchingGears {
+                value,
+                loc,
+                gear_locs,
+            } => write!(
+                f,
+                "number {value} at {loc:?} touches gears at {gear_locs:?}"
+            ),
+            SchematicIssue::OvercrowdedGear { loc, part_values } => write!(
+                f,
+                "gear at {loc:?} touches {} numbers: {part_values:?}",
+                part_values.len()
+            ),
+        }
+    }
+}
+
+fn gear_locs(schematic: &Schematic, part_num: &PartNum) -> Vec<(usize, usize)> {
+    part_num.adjacent_symbols(
+        &schematic.symbol_grid,
+        &|s| s == GEAR,
+        Adjacency::EightNeighbour,
+    )
+}
+
+fn is_part(schematic: &Schematic, part_num: &PartNum) -> bool {
+    !part_num
+        .adjacent_symbols(&schematic.symbol_grid, &|_| true, Adjacency::EightNeighbour)
+        .is_empty()
+}
+
+pub fn validate(schematic: &Schematic) -> Vec<SchematicIssue> {
+    let mut issues = Vec::new();
+
+    // The first line sets the expected width
+    if let Some(&expected_width) = schematic.line_widths.first() {
+        issues.extend(
+            schematic
+                .line_widths
+                .iter()
+                .enumerate()
+                .filter(|(_, &width)| width != expected_width)
+                .map(|(line_index, &width)| SchematicIssue::RaggedLine {
+                    line_index,
+                    width,
+                    expected_width,
+                }),
+        );
+    }
+
+    let row_starts: Vec<(usize, usize)> = schematic
+        .part_num_vec
+        .iter()
+        .filter(|part_num| part_num.loc.1 == 0)
+        .map(|part_num| part_num.loc)
+        .collect();
+    let mut gears_to_part_numbers: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
+    for part_num in &schematic.part_num_vec {
+        let (row, col) = part_num.loc;
+        if col + part_num.len == schematic.line_widths[row]
+            && row_starts.binary_search(&(row + 1, 0)).is_ok()
+        {
+            issues.push(SchematicIssue::WrappedNumber {
+                value: part_num.value,
+                loc: part_num.loc,
+            });
+        }
+
+        let gear_locs = gear_locs(schematic, part_num);
+        for &gear_loc in &gear_locs {
+            gears_to_part_numbers
+                .entry(gear_loc)
+                .or_default()
+                .push(part_num.value);
+        }
+        if gear_locs.len() > 1 {
+            issues.push(SchematicIssue::NumberTouchingGears {
+                value: part_num.value,
+                loc: part_num.loc,
+                gear_locs,
+            });
+        }
+    }
+
+    let mut overcrowded_gears: Vec<((usize, usize), Vec<u32>)> = gears_to_part_numbers
+        .into_iter()
+        .filter(|(_, part_values)| part_values.len() > 2)
+        .collect();
+    overcrowded_gears.sort();
+    issues.extend(
+        overcrowded_gears
+            .into_iter()
+            .map(|(loc, part_values)| SchematicIssue::OvercrowdedGear { loc, part_values }),
+    );
+
+    issues
+}
+
+#[derive(Debug)]
+pub enum PartChange {
+    Added {
+        value: u32,
+        loc: (usize, usize),
+        is_part: bool,
+    },
+    Removed {
+        value: u32,
+        loc: (usize, usize),
+        was_part: bool,
+    },
+    StatusChanged {
+        value: u32,
+        loc: (usize, usize),
+        is_part: bool,
+    },
+}
+
+impl fmt::Display for PartChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = |is_part: &bool| if *is_part { "part" } else { "non-part" };
+        match self {
+            PartChange::Added {
+                value,
+                loc,
+                is_part,
+            } => {
+                write!(f, "added {} {value} at {loc:?}", status(is_part))
+            }
+            PartChange::Removed {
+                value,
+                loc,
+                was_part,
+            } => write!(f, "removed {} {value} at {loc:?}", status(was_part)),
+            PartChange::StatusChanged {
+                value,
+                loc,
+                is_part,
+            } => write!(f, "{value} at {loc:?} is now a {}", status(is_part)),
+        }
+    }
+}
+
+// Numbers are matched by location and value, so an edited number shows as removed and added
+pub fn diff(before: &Schematic, after: &Schematic) -> Vec<PartChange> {
+    let part_statuses = |schematic: &Schematic| -> HashMap<((usize, usize), u32), bool> {
+        schematic
+            .part_num_vec
+            .iter()
+            .map(|part_num| ((part_num.loc, part_num.value), is_part(schematic, part_num)))
+            .collect()
+    };
+    let before_statuses = part_statuses(before);
+    let after_statuses = part_statuses(after);
+
+    let mut changes: Vec<PartChange> = before_statuses
+        .iter()
+        .filter_map(
+            |(&(loc, value), &was_part)| match after_statuses.get(&(loc, value)) {
+                None => Some(PartChange::Removed {
+                    value,
+                    loc,
+                    was_part,
+                }),
+                Some(&is_part) if is_part != was_part => Some(PartChange::StatusChanged {
+                    value,
+                    loc,
+                    is_part,
+                }),
+                Some(_) => None,
+            },
+        )
+        .collect();
+    changes.extend(
+        after_statuses
+            .iter()
+            .filter(|(key, _)| !before_statuses.contains_key(key))
+            .map(|(&(loc, value), &is_part)| PartChange::Added {
+                value,
+                loc,
+                is_part,
+            }),
+    );
+    changes.sort_by_key(|change| match change {
+        PartChange::Added { loc, .. }
+        | PartChange::Removed { loc, .. }
+        | PartChange::StatusChanged { loc, .. } => *loc,
+    });
+    changes
+}
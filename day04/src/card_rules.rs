@@ -0,0 +1,191 @@
+use super::Scratchcard;
+use std::collections::BTreeMap;
+
+pub trait ScoringRule {
+    // None when the score doesn't fit in a u64
+    fn score(&self, matches: usize) -> Option<u64>;
+}
+
+// `base^(matches - 1)`, the puzzle's doubling rule when `base` is 2
+pub struct ExponentialScoring {
+    pub base: u64,
+}
+
+impl ScoringRule for ExponentialScoring {
+    fn score(&self, matches: usize) -> Option<u64> {
+        if matches == 0 {
+            Some(0)
+        } else {
+            self.base.checked_pow(u32::try_from(matches - 1).ok()?)
+        }
+    }
+}
+
+pub struct LinearScoring {
+    pub points_per_match: u64,
+}
+
+impl ScoringRule for LinearScoring {
+    fn score(&self, matches: usize) -> Option<u64> {
+        self.points_per_match.checked_mul(matches as u64)
+    }
+}
+
+pub trait CascadeRule {
+    // Card indices won by a card; indices with no card in the deck are ignored by the caller
+    fn copy_targets(
+        &self,
+        card_index: usize,
+        matches: usize,
+        first_index: usize,
+        last_index: usize,
+    ) -> Vec<usize>;
+
+    // Rules that only copy earlier cards scratch from the end of the deck, so every copy lands
+    // on a card that hasn't been scratched yet and one pass is enough
+    fn scratches_backwards(&self) -> bool {
+        false
+    }
+}
+
+// Copies of the next `matches` cards, either stopping at the deck end or wrapping to the start
+pub struct NextCards {
+    pub wrap: bool,
+}
+
+impl CascadeRule for NextCards {
+    fn copy_targets(
+        &self,
+        card_index: usize,
+        matches: usize,
+        first_index: usize,
+        last_index: usize,
+    ) -> Vec<usize> {
+        let deck_span = last_index - first_index + 1;
+        (1..=matches)
+            .map(|offset| card_index + offset)
+            .filter_map(|target| {
+                if target <= last_index {
+                    Some(target)
+                } else if self.wrap {
+                    Some(first_index + (target - first_index) % deck_span)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+// Copies of the previous `matches` cards, stopping at the deck start
+pub struct PreviousCards;
+
+impl CascadeRule for PreviousCards {
+    fn copy_targets(
+        &self,
+        card_index: usize,
+        matches: usize,
+        first_index: usize,
+        _last_index: usize,
+    ) -> Vec<usize> {
+        (1..=matches)
+            .filter_map(|offset| card_index.checked_sub(offset))
+            .take_while(|&target| target >= first_index)
+            .collect()
+    }
+
+    fn scratches_backwards(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CascadeReport {
+    pub counts: BTreeMap<usize, u64>,
+    // Card index -> (winning card index -> copies won from it)
+    pub sources: BTreeMap<usize, BTreeMap<usize, u64>>,
+}
+
+impl CascadeReport {
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+}
+
+pub fn total_score(
+    scratchcard_vec: &[Scratchcard],
+    scoring_rule: &dyn ScoringRule,
+) -> Result<u64, String> {
+    scratchcard_vec.iter().try_fold(0u64, |total, sc| {
+        scoring_rule
+            .score(sc.matches())
+            .and_then(|score| total.checked_add(score))
+            .ok_or(format!(
+                "Score overflows a u64 at card {} ({} matches)",
+                sc.index,
+                sc.matches()
+            ))
+    })
+}
+
+// Cards are scratched in index order, or reverse index order for backwards rules. Copies of a
+// card that was already scratched this pass (from wrapping rules) wait for the next pass, so a
+// rule that keeps feeding copies back is cut off after `max_passes`.
+pub fn simulate_cascade(
+    scratchcard_vec: &[Scratchcard],
+    cascade_rule: &dyn CascadeRule,
+    max_passes: usize,
+) -> Result<CascadeReport, String> {
+    let matches: BTreeMap<usize, usize> = scratchcard_vec
+        .iter()
+        .map(|sc| (sc.index, sc.matches()))
+        .collect();
+    let (Some(&first_index), Some(&last_index)) = (matches.keys().next(), matches.keys().last())
+    else {
+        return Ok(CascadeReport::default());
+    };
+
+    let mut report = CascadeReport {
+        counts: matches.keys().map(|&index| (index, 1)).collect(),
+        sources: BTreeMap::new(),
+    };
+    let mut unscratched = report.counts.clone();
+    let mut scratch_order = matches.iter().collect::<Vec<_>>();
+    if cascade_rule.scratches_backwards() {
+        scratch_order.reverse();
+    }
+
+    for _ in 0..max_passes {
+        if unscratched.values().all(|&count| count == 0) {
+            return Ok(report);
+        }
+        for &(&card_index, &card_matches) in &scratch_order {
+            let scratched = std::mem::take(unscratched.get_mut(&card_index).unwrap());
+            if scratched == 0 {
+                continue;
+            }
+            for target in
+                cascade_rule.copy_targets(card_index, card_matches, first_index, last_index)
+            {
+                if let Some(count) = report.counts.get_mut(&target) {
+                    *count = count
+                        .checked_add(scratched)
+                        .ok_or(format!("Copies of card {target} overflow a u64"))?;
+                    *unscratched.get_mut(&target).unwrap() += scratched;
+                    *report
+                        .sources
+                        .entry(target)
+                        .or_default()
+                        .entry(card_index)
+                        .or_insert(0) += scratched;
+                }
+            }
+        }
+    }
+
+    if unscratched.values().all(|&count| count == 0) {
+        Ok(report)
+    } else {
+        Err(format!("Copies still cascading after {max_passes} passes"))
+    }
+}
@@ -1,77 +1,282 @@
-use std::fs;
+mod card_rules;
+#[allow(unused)]
+mod parse_file_error;
 
+use card_rules::{
+    CascadeReport, CascadeRule, ExponentialScoring, LinearScoring, NextCards, PreviousCards,
+    ScoringRule,
+};
+use parse_file_error::ParseFileError;
+use std::{cmp::Ordering, collections::HashSet, env, fs, str::FromStr};
+
+// Dense cards are stored as a bitset so matching is a popcount, sparse cards as a sorted set
+#[derive(Debug)]
+enum NumberSet {
+    Bitset(Vec<u64>),
+    Sorted(Vec<u32>),
+}
+
+impl NumberSet {
+    fn new(numbers: &[u32], max_number: u32) -> Self {
+        let word_count = max_number as usize / 64 + 1;
+        if word_count <= usize::max(numbers.len(), 2) {
+            let mut words = vec![0u64; word_count];
+            for &n in numbers {
+                words[n as usize / 64] |= 1 << (n % 64);
+            }
+            NumberSet::Bitset(words)
+        } else {
+            let mut sorted_numbers = numbers.to_vec();
+            sorted_numbers.sort_unstable();
+            sorted_numbers.dedup();
+            NumberSet::Sorted(sorted_numbers)
+        }
+    }
+
+    fn contains(&self, n: u32) -> bool {
+        match self {
+            NumberSet::Bitset(words) => words
+                .get(n as usize / 64)
+                .is_some_and(|word| word & (1 << (n % 64)) != 0),
+            NumberSet::Sorted(numbers) => numbers.binary_search(&n).is_ok(),
+        }
+    }
+
+    fn intersection_count(&self, other: &NumberSet) -> usize {
+        match (self, other) {
+            (NumberSet::Bitset(words), NumberSet::Bitset(other_words)) => words
+                .iter()
+                .zip(other_words)
+                .map(|(word, other_word)| (word & other_word).count_ones() as usize)
+                .sum(),
+            (NumberSet::Sorted(numbers), NumberSet::Sorted(other_numbers)) => {
+                let (mut i, mut j, mut count) = (0, 0, 0);
+                while i < numbers.len() && j < other_numbers.len() {
+                    match numbers[i].cmp(&other_numbers[j]) {
+                        Ordering::Less => i += 1,
+                        Ordering::Greater => j += 1,
+                        Ordering::Equal => {
+                            count += 1;
+                            i += 1;
+                            j += 1;
+                        }
+                    }
+                }
+                count
+            }
+            (NumberSet::Sorted(numbers), bitset) | (bitset, NumberSet::Sorted(numbers)) => {
+                numbers.iter().filter(|&&n| bitset.contains(n)).count()
+            }
+        }
+    }
+}
+
+// A card number that appears more than once is a match each time it appears, so repeats beyond
+// the first are kept aside from the set
 #[derive(Debug)]
 struct Scratchcard {
     index: usize,
-    winning_number: Vec<u32>,
-    card_numbers: Vec<u32>,
+    winning_number: NumberSet,
+    card_numbers: NumberSet,
+    repeated_card_numbers: Vec<u32>,
 }
 
 impl Scratchcard {
     fn matches(&self) -> usize {
-        self
-            .card_numbers
+        self.card_numbers.intersection_count(&self.winning_number)
+            + self
+                .repeated_card_numbers
+                .iter()
+                .filter(|&&n| self.winning_number.contains(n))
+                .count()
+    }
+}
+
+// Columns are 1-based and relative to the whole line, `offset` being where `numbers` starts
+fn parse_numbers(numbers: &str, offset: usize) -> Result<Vec<u32>, ParseFileError> {
+    numbers
+        .split_whitespace()
+        .map(|token| {
+            let column = offset + (token.as_ptr() as usize - numbers.as_ptr() as usize) + 1;
+            token.parse::<u32>().map_err(|err| {
+                ParseFileError::from_str(format!(
+                    "invalid number '{token}' at column {column}: {err}"
+                ))
+            })
+        })
+        .collect()
+}
+
+impl FromStr for Scratchcard {
+    type Err = ParseFileError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (label, numbers) = s
+            .split_once(':')
+            .ok_or(ParseFileError::from_str(String::from(
+                "missing ':' after card label",
+            )))?;
+        let index = label
+            .strip_prefix("Card")
+            .and_then(|index| index.trim().parse::<usize>().ok())
+            .ok_or(ParseFileError::from_str(format!(
+                "invalid card label '{label}' at column 1"
+            )))?;
+        let (winning_numbers, card_numbers) =
+            numbers
+                .split_once('|')
+                .ok_or(ParseFileError::from_str(format!(
+                    "missing '|' after column {}",
+                    label.len() + 1
+                )))?;
+
+        let winning_offset = label.len() + 1;
+        let card_offset = winning_offset + winning_numbers.len() + 1;
+        let winning_numbers = parse_numbers(winning_numbers, winning_offset)?;
+        let card_numbers = parse_numbers(card_numbers, card_offset)?;
+
+        // Both sets share a width so bitsets can be intersected word by word
+        let max_number = winning_numbers
+            .iter()
+            .chain(&card_numbers)
+            .copied()
+            .max()
+            .unwrap_or(0);
+        let mut seen_card_numbers = HashSet::new();
+        let repeated_card_numbers = card_numbers
             .iter()
-            .filter(|n| self.winning_number.contains(n))
-            .count()
+            .copied()
+            .filter(|&n| !seen_card_numbers.insert(n))
+            .collect();
+        Ok(Scratchcard {
+            index,
+            winning_number: NumberSet::new(&winning_numbers, max_number),
+            card_numbers: NumberSet::new(&card_numbers, max_number),
+            repeated_card_numbers,
+        })
     }
+}
 
-    fn score(&self) -> u32 {
-        let matches = self.matches() as u32;
-        if matches == 0 {
-            0
-        } else {
-            2u32.pow(matches - 1)
+fn at_line(err: ParseFileError, line_number: usize) -> ParseFileError {
+    match err {
+        ParseFileError::CustomError(err_str) => {
+            ParseFileError::from_str(format!("line {line_number}: {err_str}"))
         }
+        err => err,
     }
 }
 
-fn parse_scratchcard(line: &str) -> Scratchcard {
-    let line_split = line.split(&[':', '|']).collect::<Vec<&str>>();
-    Scratchcard {
-        index: line_split[0]
-            .split_whitespace()
-            .last()
-            .unwrap()
-            .parse()
-            .unwrap(),
-        winning_number: line_split[1]
-            .split_whitespace()
-            .map(|s| s.parse::<u32>().unwrap())
-            .collect(),
-        card_numbers: line_split[2]
-            .split_whitespace()
-            .map(|s| s.parse::<u32>().unwrap())
-            .collect(),
-    }
+fn parse_scratchcards(file_contents: &str) -> Result<Vec<Scratchcard>, ParseFileError> {
+    let mut seen_indices = HashSet::new();
+    file_contents
+        .lines()
+        .zip(1..)
+        .map(|(line, line_number)| {
+            let scratchcard = line
+                .parse::<Scratchcard>()
+                .map_err(|err| at_line(err, line_number))?;
+            if !seen_indices.insert(scratchcard.index) {
+                return Err(ParseFileError::from_str(format!(
+                    "line {line_number}: duplicate card index {}",
+                    scratchcard.index
+                )));
+            }
+            Ok(scratchcard)
+        })
+        .collect()
 }
 
-fn part_one(scratchcard_vec: &Vec<Scratchcard>) -> u32 {
-    scratchcard_vec.into_iter().map(|sc| sc.score()).sum()
+// Indices between 1 and the highest card index that have no card
+fn missing_card_indices(scratchcard_vec: &[Scratchcard]) -> Vec<usize> {
+    let indices: HashSet<usize> = scratchcard_vec.iter().map(|sc| sc.index).collect();
+    let max_index = indices.iter().copied().max().unwrap_or(0);
+    (1..=max_index).filter(|i| !indices.contains(i)).collect()
 }
 
-fn part_two(scratchcard_vec: &Vec<Scratchcard>) -> u32 {
-    // Prepare scratchcard counts vector
-    let mut scratchcard_counts: Vec<u32> = vec![1u32; scratchcard_vec.len() + 1];
-    scratchcard_counts[0] = 0;
+const MAX_CASCADE_PASSES: usize = 1000;
 
-    for scratchcard in scratchcard_vec {
-        for i in 0usize..scratchcard.matches() {
-            let update_index = scratchcard.index + i + 1;
-            if update_index < scratchcard_counts.len() {
-                scratchcard_counts[update_index] += scratchcard_counts[scratchcard.index];
-            }
-        }
-    }
-    
-    scratchcard_counts.into_iter().sum()
+fn part_one(
+    scratchcard_vec: &[Scratchcard],
+    scoring_rule: &dyn ScoringRule,
+) -> Result<u64, String> {
+    card_rules::total_score(scratchcard_vec, scoring_rule)
+}
+
+fn part_two(
+    scratchcard_vec: &[Scratchcard],
+    cascade_rule: &dyn CascadeRule,
+) -> Result<CascadeReport, String> {
+    card_rules::simulate_cascade(scratchcard_vec, cascade_rule, MAX_CASCADE_PASSES)
 }
 
 fn main() {
     let file_path = "input.txt";
     let file_contents = fs::read_to_string(file_path).expect("Unable to read file: {file_path}");
-    let scratchcard_vec: Vec<Scratchcard> = file_contents.lines().map(parse_scratchcard).collect();
+    let scratchcard_vec = match parse_scratchcards(&file_contents) {
+        Ok(scratchcard_vec) => scratchcard_vec,
+        Err(err) => {
+            println!("Error parsing file contents: {err}");
+            return;
+        }
+    };
 
-    println!("Part One -- Sum of Score: {}", part_one(&scratchcard_vec));
-    println!("Part Two -- Total Number of Tickets: {}", part_two(&scratchcard_vec));
+    let missing_indices = missing_card_indices(&scratchcard_vec);
+    if !missing_indices.is_empty() {
+        println!("Warning -- Missing card indices: {missing_indices:?}");
+    }
+
+    // Defaults are the puzzle's rules: `--scoring=exponential --cascade=next`
+    let mut scoring_rule: Box<dyn ScoringRule> = Box::new(ExponentialScoring { base: 2 });
+    let mut cascade_rule: Box<dyn CascadeRule> = Box::new(NextCards { wrap: false });
+    let mut show_breakdown = false;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--scoring=exponential" => scoring_rule = Box::new(ExponentialScoring { base: 2 }),
+            "--scoring=linear" => {
+                scoring_rule = Box::new(LinearScoring {
+                    points_per_match: 1,
+                })
+            }
+            "--cascade=next" => cascade_rule = Box::new(NextCards { wrap: false }),
+            "--cascade=wrap" => cascade_rule = Box::new(NextCards { wrap: true }),
+            "--cascade=previous" => cascade_rule = Box::new(PreviousCards),
+            "--breakdown" => show_breakdown = true,
+            _ => {
+                println!("Unknown argument: {arg}");
+                return;
+            }
+        }
+    }
+
+    match part_one(&scratchcard_vec, scoring_rule.as_ref()) {
+        Ok(score) => println!("Part One -- Sum of Score: {score}"),
+        Err(err) => println!("Error scoring cards: {err}"),
+    }
+    let cascade_report = match part_two(&scratchcard_vec, cascade_rule.as_ref()) {
+        Ok(cascade_report) => cascade_report,
+        Err(err) => {
+            println!("Error simulating copies: {err}");
+            return;
+        }
+    };
+    println!(
+        "Part Two -- Total Number of Tickets: {}",
+        cascade_report.total()
+    );
+
+    if show_breakdown {
+        for (card_index, count) in &cascade_report.counts {
+            let sources = cascade_report
+                .sources
+                .get(card_index)
+                .map(|sources| {
+                    sources
+                        .iter()
+                        .map(|(winner, copies)| format!("{copies} from card {winner}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_default();
+            println!("  Card {card_index}: {count} ({sources})");
+        }
+    }
 }
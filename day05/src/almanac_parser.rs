@@ -0,0 +1,121 @@
+use super::{AlmanacMap, MapEntry};
+use crate::parse_file_error::ParseFileError;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub struct Almanac {
+    pub seed_vec: Vec<usize>,
+    pub almanac_maps: Vec<AlmanacMap>,
+}
+
+// Source category, dest category and entries with their line numbers, for the map being read
+type PendingMap = (String, String, Vec<(usize, MapEntry)>);
+
+fn line_error(line_number: usize, err_str: &str) -> ParseFileError {
+    ParseFileError::from_str(format!("line {line_number}: {err_str}"))
+}
+
+fn parse_map_header(line: &str, line_number: usize) -> Result<(String, String), ParseFileError> {
+    let categories = line.strip_suffix(" map:").ok_or(line_error(
+        line_number,
+        "expected '<source>-to-<dest> map:'",
+    ))?;
+    let (source_category, dest_category) = categories.split_once("-to-").ok_or(line_error(
+        line_number,
+        "expected '<source>-to-<dest> map:'",
+    ))?;
+    if source_category.is_empty() || dest_category.is_empty() {
+        return Err(line_error(line_number, "empty category name"));
+    }
+    Ok((source_category.to_string(), dest_category.to_string()))
+}
+
+// Entries are sorted by source start, then neighbours are checked for overlap
+fn finish_map(
+    source_category: String,
+    dest_category: String,
+    mut numbered_entries: Vec<(usize, MapEntry)>,
+) -> Result<AlmanacMap, ParseFileError> {
+    numbered_entries.sort_by_key(|(_, e)| e.source_range_start);
+    for pair in numbered_entries.windows(2) {
+        let ((line_a, entry_a), (line_b, entry_b)) = (&pair[0], &pair[1]);
+        if entry_a.source_range_start + entry_a.range_length > entry_b.source_range_start {
+            return Err(ParseFileError::from_str(format!(
+                "{source_category}-to-{dest_category} map: source ranges on lines {} and {} overlap",
+                usize::min(*line_a, *line_b),
+                usize::max(*line_a, *line_b)
+            )));
+        }
+    }
+    Ok(AlmanacMap {
+        source_category,
+        dest_category,
+        map_entries: numbered_entries.into_iter().map(|(_, e)| e).collect(),
+    })
+}
+
+impl FromStr for Almanac {
+    type Err = ParseFileError;
+
+    // `lines` already strips a trailing '\r', so CRLF files parse the same as LF ones
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut numbered_lines = s
+            .lines()
+            .zip(1usize..)
+            .map(|(line, line_number)| (line.trim(), line_number))
+            .filter(|(line, _)| !line.is_empty());
+
+        let (seeds_line, seeds_line_number) = numbered_lines
+            .next()
+            .ok_or(ParseFileError::from_str(String::from("empty almanac")))?;
+        let seed_vec = seeds_line
+            .strip_prefix("seeds:")
+            .ok_or(line_error(seeds_line_number, "expected 'seeds:'"))?
+            .split_whitespace()
+            .map(|seed| {
+                seed.parse::<usize>().map_err(|err| {
+                    line_error(seeds_line_number, &format!("invalid seed '{seed}': {err}"))
+                })
+            })
+            .collect::<Result<Vec<usize>, ParseFileError>>()?;
+
+        let mut almanac_maps = Vec::new();
+        let mut current_map: Option<PendingMap> = None;
+        for (line, line_number) in numbered_lines {
+            if line.ends_with("map:") {
+                if let Some((source_category, dest_category, numbered_entries)) = current_map.take()
+                {
+                    almanac_maps.push(finish_map(
+                        source_category,
+                        dest_category,
+                        numbered_entries,
+                    )?);
+                }
+                let (source_category, dest_category) = parse_map_header(line, line_number)?;
+                current_map = Some((source_category, dest_category, Vec::new()));
+                continue;
+            }
+
+            let (_, _, numbered_entries) = current_map
+                .as_mut()
+                .ok_or(line_error(line_number, "map entry before any map header"))?;
+            let map_entry = line.parse::<MapEntry>().map_err(|err| match err {
+                ParseFileError::CustomError(err_str) => line_error(line_number, &err_str),
+                err => err,
+            })?;
+            numbered_entries.push((line_number, map_entry));
+        }
+        if let Some((source_category, dest_category, numbered_entries)) = current_map {
+            almanac_maps.push(finish_map(
+                source_category,
+                dest_category,
+                numbered_entries,
+            )?);
+        }
+
+        Ok(Almanac {
+            seed_vec,
+            almanac_maps,
+        })
+    }
+}
@@ -0,0 +1,136 @@
+use super::AlmanacMap;
+use std::{
+    collections::{HashMap, HashSet},
+    error, fmt,
+};
+
+#[derive(Debug)]
+pub enum CategoryGraphError {
+    UnknownCategory(String),
+    DuplicateLink(String, String),
+    MissingLink(String, String),
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for CategoryGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CategoryGraphError::UnknownCategory(category) => {
+                write!(f, "Unknown category: {category}")
+            }
+            CategoryGraphError::DuplicateLink(source, dest) => {
+                write!(f, "More than one {source}-to-{dest} map")
+            }
+            CategoryGraphError::MissingLink(source, dest) => {
+                write!(f, "No chain of maps leads from {source} to {dest}")
+            }
+            CategoryGraphError::Cycle(categories) => {
+                write!(f, "Maps form a cycle: {}", categories.join(" -> "))
+            }
+        }
+    }
+}
+
+impl error::Error for CategoryGraphError {}
+
+// Almanac maps as edges between categories, so any category can be mapped to any other
+#[derive(Debug)]
+pub struct CategoryGraph {
+    almanac_maps: Vec<AlmanacMap>,
+    // Source category -> indices into almanac_maps
+    edges: HashMap<String, Vec<usize>>,
+}
+
+impl CategoryGraph {
+    pub fn new(almanac_maps: Vec<AlmanacMap>) -> Result<Self, CategoryGraphError> {
+        let mut edges: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut links = HashSet::new();
+        for (map_index, almanac_map) in almanac_maps.iter().enumerate() {
+            let link = (&almanac_map.source_category, &almanac_map.dest_category);
+            if !links.insert(link) {
+                return Err(CategoryGraphError::DuplicateLink(
+                    almanac_map.source_category.clone(),
+                    almanac_map.dest_category.clone(),
+                ));
+            }
+            edges
+                .entry(almanac_map.source_category.clone())
+                .or_default()
+                .push(map_index);
+        }
+        Ok(CategoryGraph {
+            almanac_maps,
+            edges,
+        })
+    }
+
+    fn has_category(&self, category: &str) -> bool {
+        self.almanac_maps.iter().any(|almanac_map| {
+            almanac_map.source_category == category || almanac_map.dest_category == category
+        })
+    }
+
+    // Depth-first from `source`; any cycle reachable from it is an error even if `dest` is found
+    pub fn path(&self, source: &str, dest: &str) -> Result<Vec<&AlmanacMap>, CategoryGraphError> {
+        for category in [source, dest] {
+            if !self.has_category(category) {
+                return Err(CategoryGraphError::UnknownCategory(category.to_string()));
+            }
+        }
+
+        let mut on_stack = Vec::new();
+        let mut finished = HashSet::new();
+        let mut found_path = None;
+        self.search(source, dest, &mut on_stack, &mut finished, &mut found_path)?;
+
+        found_path
+            .map(|map_indices: Vec<usize>| {
+                map_indices
+                    .into_iter()
+                    .map(|map_index| &self.almanac_maps[map_index])
+                    .collect()
+            })
+            .ok_or(CategoryGraphError::MissingLink(
+                source.to_string(),
+                dest.to_string(),
+            ))
+    }
+
+    fn search<'a>(
+        &'a self,
+        category: &'a str,
+        dest: &str,
+        on_stack: &mut Vec<(&'a str, Option<usize>)>,
+        finished: &mut HashSet<&'a str>,
+        found_path: &mut Option<Vec<usize>>,
+    ) -> Result<(), CategoryGraphError> {
+        if category == dest && found_path.is_none() {
+            *found_path = Some(on_stack.iter().filter_map(|&(_, map)| map).collect());
+        }
+        for &map_index in self.edges.get(category).into_iter().flatten() {
+            let next_category = self.almanac_maps[map_index].dest_category.as_str();
+            if let Some(cycle_start) = on_stack.iter().position(|&(c, _)| c == next_category) {
+                let mut cycle: Vec<String> = on_stack[cycle_start..]
+                    .iter()
+                    .map(|&(c, _)| c.to_string())
+                    .collect();
+                cycle.extend([category.to_string(), next_category.to_string()]);
+                return Err(CategoryGraphError::Cycle(cycle));
+            }
+            if next_category == category {
+                return Err(CategoryGraphError::Cycle(vec![
+                    category.to_string(),
+                    next_category.to_string(),
+                ]));
+            }
+            if finished.contains(next_category) {
+                continue;
+            }
+            on_stack.push((category, Some(map_index)));
+            self.search(next_category, dest, on_stack, finished, found_path)?;
+            on_stack.pop();
+        }
+        finished.insert(category);
+        Ok(())
+    }
+}
@@ -1,8 +1,13 @@
-use regex::Regex;
-use std::collections::HashMap;
-use std::fs;
-use std::slice::Iter;
+mod almanac_parser;
+mod category_graph;
+#[allow(unused)]
+mod parse_file_error;
+
+use almanac_parser::Almanac;
+use category_graph::CategoryGraph;
+use parse_file_error::ParseFileError;
 use std::str::FromStr;
+use std::{env, fs};
 
 #[derive(Debug)]
 struct MapEntry {
@@ -13,24 +18,27 @@ struct MapEntry {
 }
 
 impl FromStr for MapEntry {
-    type Err = Box<dyn std::error::Error>;
+    type Err = ParseFileError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split_iter = s.split_whitespace();
-        let dest_range_start = split_iter.next().ok_or("No dest range")?.parse::<usize>()?;
-        let source_range_start = split_iter
-            .next()
-            .ok_or("No source range")?
-            .parse::<usize>()?;
-        let range_length = split_iter
-            .next()
-            .ok_or("No range length")?
-            .parse::<usize>()?;
-        assert!(split_iter.next().is_none());
+        let values = s
+            .split_whitespace()
+            .map(|value| {
+                value.parse::<usize>().map_err(|err| {
+                    ParseFileError::from_str(format!("invalid map value '{value}': {err}"))
+                })
+            })
+            .collect::<Result<Vec<usize>, ParseFileError>>()?;
+        let [dest_range_start, source_range_start, range_length] = values[..] else {
+            return Err(ParseFileError::from_str(format!(
+                "expected 3 values in map entry, found {}",
+                values.len()
+            )));
+        };
 
         Ok(MapEntry {
-            source_range_start: source_range_start,
-            range_length: range_length,
+            source_range_start,
+            range_length,
             offset: (dest_range_start as i64) - (source_range_start as i64),
         })
     }
@@ -45,117 +53,321 @@ struct AlmanacMap {
 }
 
 impl AlmanacMap {
-    fn apply_mapping(&self, index: usize) -> usize {
-        for map_entry in &self.map_entries {
-            if index < map_entry.source_range_start {
-                return index;
+    fn identity(category: &str) -> Self {
+        AlmanacMap {
+            source_category: category.to_string(),
+            dest_category: category.to_string(),
+            map_entries: Vec::new(),
+        }
+    }
+
+    fn lookup(&self, index: usize) -> usize {
+        let entry_index = self
+            .map_entries
+            .partition_point(|e| e.source_range_start + e.range_length <= index);
+        match self.map_entries.get(entry_index) {
+            Some(map_entry) if map_entry.source_range_start <= index => {
+                ((index as i64) + map_entry.offset) as usize
+            }
+            _ => index,
+        }
+    }
+
+    // Splits a (start, length) range at map entry boundaries, returning each piece with the
+    // offset it is mapped by (zero outside of any entry)
+    fn segments(&self, (range_start, range_length): (usize, usize)) -> Vec<(usize, usize, i64)> {
+        let range_end = range_start + range_length;
+        let mut segments = Vec::new();
+        let mut cursor = range_start;
+
+        let first_entry = self
+            .map_entries
+            .partition_point(|e| e.source_range_start + e.range_length <= range_start);
+        for map_entry in &self.map_entries[first_entry..] {
+            let entry_end = map_entry.source_range_start + map_entry.range_length;
+            if map_entry.source_range_start >= range_end {
+                break;
+            }
+            if cursor < map_entry.source_range_start {
+                segments.push((cursor, map_entry.source_range_start - cursor, 0));
+                cursor = map_entry.source_range_start;
+            }
+            let overlap_end = usize::min(entry_end, range_end);
+            segments.push((cursor, overlap_end - cursor, map_entry.offset));
+            cursor = overlap_end;
+        }
+        if cursor < range_end {
+            segments.push((cursor, range_end - cursor, 0));
+        }
+        segments
+    }
+
+    // Maps a whole (start, length) range, splitting it wherever it crosses a map entry boundary
+    fn apply_mapping_to_range(&self, range: (usize, usize)) -> Vec<(usize, usize)> {
+        self.segments(range)
+            .into_iter()
+            .map(|(start, length, offset)| (((start as i64) + offset) as usize, length))
+            .collect()
+    }
+
+    fn entries_end(&self) -> usize {
+        self.map_entries
+            .last()
+            .map(|e| e.source_range_start + e.range_length)
+            .unwrap_or(0)
+    }
+
+    // A single map equivalent to applying `self` then `next`. Entries stay sorted and
+    // non-overlapping, and adjacent entries with the same offset are joined.
+    fn compose(&self, next: &AlmanacMap) -> AlmanacMap {
+        debug_assert_eq!(self.dest_category, next.source_category);
+        // Past both maps' entries everything is the identity
+        let domain_end = usize::max(self.entries_end(), next.entries_end());
+
+        let mut map_entries: Vec<MapEntry> = Vec::new();
+        for (start, length, offset) in self.segments((0, domain_end)) {
+            let image_start = ((start as i64) + offset) as usize;
+            for (next_start, next_length, next_offset) in next.segments((image_start, length)) {
+                let source_range_start = ((next_start as i64) - offset) as usize;
+                let total_offset = offset + next_offset;
+                if total_offset == 0 {
+                    continue;
+                }
+                match map_entries.last_mut() {
+                    Some(last)
+                        if last.offset == total_offset
+                            && last.source_range_start + last.range_length
+                                == source_range_start =>
+                    {
+                        last.range_length += next_length;
+                    }
+                    _ => map_entries.push(MapEntry {
+                        source_range_start,
+                        range_length: next_length,
+                        offset: total_offset,
+                    }),
+                }
             }
-            if index < map_entry.source_range_start + map_entry.range_length {
-                return ((index as i64) + map_entry.offset) as usize;
+        }
+
+        AlmanacMap {
+            source_category: self.source_category.clone(),
+            dest_category: next.dest_category.clone(),
+            map_entries,
+        }
+    }
+
+    // Every (start, length) source range whose image lies in `target`, merged. Any map has
+    // these preimages; `invert` is the special case where each is a single value.
+    fn preimage(&self, (target_start, target_length): (usize, usize)) -> Vec<(usize, usize)> {
+        let target_end = target_start + target_length;
+        let domain_end = self.entries_end();
+        let mut source_ranges = Vec::new();
+        for (start, length, offset) in self.segments((0, domain_end)) {
+            let image_start = ((start as i64) + offset) as usize;
+            let overlap_start = usize::max(image_start, target_start);
+            let overlap_end = usize::min(image_start + length, target_end);
+            if overlap_start < overlap_end {
+                source_ranges.push((
+                    ((overlap_start as i64) - offset) as usize,
+                    overlap_end - overlap_start,
+                ));
+            }
+        }
+        // Past the last entry the map is the identity
+        let tail_start = usize::max(domain_end, target_start);
+        if tail_start < target_end {
+            source_ranges.push((tail_start, target_end - tail_start));
+        }
+        merge_ranges(source_ranges)
+    }
+
+    // Only a bijection can be inverted: the entries' destination ranges must cover exactly
+    // the same values as their source ranges, so the identity elsewhere stays consistent
+    fn invert(&self) -> Result<AlmanacMap, String> {
+        let source_ranges = merge_ranges(
+            self.map_entries
+                .iter()
+                .map(|e| (e.source_range_start, e.range_length))
+                .collect(),
+        );
+        let dest_ranges = merge_ranges(
+            self.map_entries
+                .iter()
+                .map(|e| {
+                    (
+                        ((e.source_range_start as i64) + e.offset) as usize,
+                        e.range_length,
+                    )
+                })
+                .collect(),
+        );
+        let dest_length: usize = self.map_entries.iter().map(|e| e.range_length).sum();
+        let source_length: usize = source_ranges.iter().map(|&(_, length)| length).sum();
+        if source_ranges != dest_ranges || dest_length != source_length {
+            return Err(format!(
+                "{}-to-{} map is not a bijection",
+                self.source_category, self.dest_category
+            ));
+        }
+
+        let mut map_entries: Vec<MapEntry> = self
+            .map_entries
+            .iter()
+            .map(|e| MapEntry {
+                source_range_start: ((e.source_range_start as i64) + e.offset) as usize,
+                range_length: e.range_length,
+                offset: -e.offset,
+            })
+            .collect();
+        map_entries.sort_by_key(|e| e.source_range_start);
+
+        Ok(AlmanacMap {
+            source_category: self.dest_category.clone(),
+            dest_category: self.source_category.clone(),
+            map_entries,
+        })
+    }
+}
+
+// Sorts ranges and merges any that overlap or touch, so fragments don't pile up between maps
+fn merge_ranges(mut ranges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.retain(|&(_, range_length)| range_length > 0);
+    ranges.sort();
+    let mut merged_ranges: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (range_start, range_length) in ranges {
+        match merged_ranges.last_mut() {
+            Some((last_start, last_length)) if range_start <= *last_start + *last_length => {
+                *last_length = usize::max(*last_start + *last_length, range_start + range_length)
+                    - *last_start;
             }
+            _ => merged_ranges.push((range_start, range_length)),
         }
-        index
     }
+    merged_ranges
 }
 
-fn map_seeds_to_min_location(
-    seed_iter: Iter<usize>,
-    almanac_maps: &HashMap<(String, String), AlmanacMap>,
-) -> usize {
-    let seed_soil_map = almanac_maps
-        .get(&(String::from("seed"), String::from("soil")))
-        .unwrap();
-    let soil_fertilizer_map = almanac_maps
-        .get(&(String::from("soil"), String::from("fertilizer")))
-        .unwrap();
-    let fertilizer_water_map = almanac_maps
-        .get(&(String::from("fertilizer"), String::from("water")))
-        .unwrap();
-    let water_light_map = almanac_maps
-        .get(&(String::from("water"), String::from("light")))
-        .unwrap();
-    let light_temp_map = almanac_maps
-        .get(&(String::from("light"), String::from("temperature")))
-        .unwrap();
-    let temp_humidity_map = almanac_maps
-        .get(&(String::from("temperature"), String::from("humidity")))
-        .unwrap();
-    let humidity_location_map = almanac_maps
-        .get(&(String::from("humidity"), String::from("location")))
-        .unwrap();
-
-    let mut min_location = usize::MAX;
-    seed_iter
-        .map(|i| seed_soil_map.apply_mapping(*i))
-        .map(|i| soil_fertilizer_map.apply_mapping(i))
-        .map(|i| fertilizer_water_map.apply_mapping(i))
-        .map(|i| water_light_map.apply_mapping(i))
-        .map(|i| light_temp_map.apply_mapping(i))
-        .map(|i| temp_humidity_map.apply_mapping(i))
-        .map(|i| humidity_location_map.apply_mapping(i))
-        .for_each(|i| {
-            if i <= min_location {
-                min_location = i
+// Values in both sets of (start, length) ranges
+fn intersect_ranges(a: &[(usize, usize)], b: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut overlaps = Vec::new();
+    for &(a_start, a_length) in a {
+        for &(b_start, b_length) in b {
+            let start = usize::max(a_start, b_start);
+            let end = usize::min(a_start + a_length, b_start + b_length);
+            if start < end {
+                overlaps.push((start, end - start));
             }
-        });
-    min_location
+        }
+    }
+    merge_ranges(overlaps)
+}
+
+fn map_ranges_to_min_value(ranges: &[(usize, usize)], almanac_map: &AlmanacMap) -> usize {
+    merge_ranges(
+        ranges
+            .iter()
+            .flat_map(|&range| almanac_map.apply_mapping_to_range(range))
+            .collect(),
+    )
+    .first()
+    .map(|&(range_start, _)| range_start)
+    .unwrap_or(usize::MAX)
 }
 
 fn main() {
     let file_path = "input.txt";
     let file_contents = fs::read_to_string(file_path).expect("Unable to read file: {file_path}");
 
-    let almanac_file_regex =
-        Regex::new(r"seeds:\s([0-9 ]+)\s+((?:\w+-\w+-\w+ map:\s+[0-9\s]*)*)").unwrap();
-    let mapping_regex = Regex::new(r"(\w+)-\w+-(\w+)\s+map:\s+([0-9\s]*)(?:(?:\s+)|\z)").unwrap();
-
-    let almanac_captures = almanac_file_regex
-        .captures(&file_contents)
-        .expect("Almanac file did not match");
-    let seeds = almanac_captures.get(1).unwrap().as_str().to_owned();
-    let all_mappings = almanac_captures.get(2).unwrap().as_str().to_owned();
-
-    let seed_vec = seeds
-        .split(" ")
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect::<Vec<usize>>();
-    println!("{seed_vec:?}");
-
-    let almanac_maps = mapping_regex
-        .captures_iter(&all_mappings)
-        .map(|m| AlmanacMap {
-            source_category: m.get(1).unwrap().as_str().to_owned(),
-            dest_category: m.get(2).unwrap().as_str().to_owned(),
-            map_entries: m
-                .get(3)
-                .unwrap()
-                .as_str()
-                .lines()
-                .filter_map(|e| e.parse().ok())
-                .collect(),
-        })
-        .map(|mut m: AlmanacMap| {
-            m.map_entries.sort_by_key(|e| e.source_range_start);
-            ((m.source_category.clone(), m.dest_category.clone()), m)
-        })
-        .collect::<HashMap<(String, String), AlmanacMap>>();
-
-    // let part_one_min_location = map_seeds_to_min_location(seed_vec.iter(), &almanac_maps);
-    // println!(
-    //     "Part One -- Minimum Location Value: {}",
-    //     part_one_min_location
-    // );
-
-    let mut updated_seed_vec = Vec::<usize>::new();
-    seed_vec.chunks(2).into_iter().for_each(|c| {
-        let (range_start, range_length) = (c[0], c[1]);
-        updated_seed_vec.extend(range_start..range_start + range_length);
-    });
-    // println!("New Vec Len: {}", updated_seed_vec.len());
-
-    let part_two_min_location = map_seeds_to_min_location(updated_seed_vec.iter(), &almanac_maps);
+    let Almanac {
+        seed_vec,
+        almanac_maps,
+    } = match file_contents.parse::<Almanac>() {
+        Ok(almanac) => almanac,
+        Err(err) => {
+            println!("Error parsing file contents: {err}");
+            return;
+        }
+    };
+
+    let category_graph = match CategoryGraph::new(almanac_maps) {
+        Ok(category_graph) => category_graph,
+        Err(err) => {
+            println!("Error building category graph: {err}");
+            return;
+        }
+    };
+    // Seeds are mapped to locations by default, or to `day05 <dest category>` if given
+    let dest_category = env::args().nth(1).unwrap_or(String::from("location"));
+    let path = match category_graph.path("seed", &dest_category) {
+        Ok(path) => path,
+        Err(err) => {
+            println!("Error finding seed to {dest_category} maps: {err}");
+            return;
+        }
+    };
+
+    let seed_to_dest_map = path
+        .into_iter()
+        .fold(AlmanacMap::identity("seed"), |composed_map, almanac_map| {
+            composed_map.compose(almanac_map)
+        });
+
+    let part_one_min_location = seed_vec
+        .iter()
+        .map(|&seed| seed_to_dest_map.lookup(seed))
+        .min()
+        .unwrap_or(usize::MAX);
     println!(
-        "Part Two -- Minimum Location Value: {}",
-        part_two_min_location
+        "Part One -- Minimum {dest_category} Value: {}",
+        part_one_min_location
     );
+
+    // Part two reads the seeds as (start, length) pairs
+    if seed_vec.len() % 2 != 0 {
+        println!(
+            "Part Two -- Expected seed start and length pairs, found {} values",
+            seed_vec.len()
+        );
+    } else {
+        let seed_ranges = seed_vec
+            .chunks_exact(2)
+            .map(|c| (c[0], c[1]))
+            .collect::<Vec<_>>();
+        let part_two_min_location = map_ranges_to_min_value(&seed_ranges, &seed_to_dest_map);
+        println!(
+            "Part Two -- Minimum {dest_category} Value: {}",
+            part_two_min_location
+        );
+
+        // Work back from the minimum to the smallest seed that reaches it, with a plain lookup
+        // when the composed map can be inverted
+        let candidate_seeds = match seed_to_dest_map.invert() {
+            Ok(dest_to_seed_map) => vec![(dest_to_seed_map.lookup(part_two_min_location), 1)],
+            Err(_) => seed_to_dest_map.preimage((part_two_min_location, 1)),
+        };
+        match intersect_ranges(&candidate_seeds, &seed_ranges).first() {
+            Some(&(seed, _)) => println!("Seed reaching minimum {dest_category}: {seed}"),
+            None => println!("No seed reaches minimum {dest_category}"),
+        }
+    }
+
+    // `day05 <dest category> <start>,<length>` lists the seed ranges that land in that range
+    if let Some(target) = env::args().nth(2) {
+        let Some((Ok(start), Ok(length))) = target
+            .split_once(',')
+            .map(|(start, length)| (start.parse::<usize>(), length.parse::<usize>()))
+        else {
+            println!("Expected a <start>,<length> range, got: {target}");
+            return;
+        };
+        println!(
+            "Seed ranges reaching {dest_category} {start}..{}: {:?}",
+            start + length,
+            seed_to_dest_map
+                .preimage((start, length))
+                .into_iter()
+                .map(|(seed_start, seed_length)| seed_start..seed_start + seed_length)
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -1,61 +1,355 @@
-use std::fs;
+#[allow(unused)]
+mod parse_file_error;
 
-fn count_record_strategies(time: u64, distance: u64) -> u64 {
-    let record_cutoff: u64 = Vec::from_iter(0..time / 2)
-        .as_slice()
-        .partition_point(|&t| t * (time - t) <= distance)
-        .try_into()
-        .expect("Could not calculate record cutoff");
+use parse_file_error::ParseFileError;
+use std::{env, fmt, fs, str::FromStr};
 
-    time - 2 * record_cutoff + 1
+// Holding the button for `hold_time` ms charges the boat to
+// `min(max_speed, acceleration * hold_time^charge_exponent)` mm/ms
+#[derive(Debug, Clone, Copy)]
+struct RaceModel {
+    acceleration: u128,
+    max_speed: Option<u128>,
+    charge_exponent: u32,
 }
 
-fn part_one(file_contents: &str) -> u64 {
-    let mut file_lines = file_contents.lines();
+// The puzzle's boats, used for both parts
+const BOAT_RACE: RaceModel = RaceModel {
+    acceleration: 1,
+    max_speed: None,
+    charge_exponent: 1,
+};
 
-    let times_iter = file_lines
-        .next()
-        .expect("Unable to read line")
-        .split_whitespace()
-        .filter_map(|s| s.parse::<u64>().ok());
-    let dist_iter = file_lines
-        .next()
-        .expect("Unable to read line")
+#[derive(Debug)]
+struct RaceReport {
+    winning_count: u128,
+    winning_range: Option<(u128, u128)>,
+    optimal_hold_time: u128,
+}
+
+impl fmt::Display for RaceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.winning_range {
+            Some((first_win, last_win)) => write!(
+                f,
+                "{} winning hold times from {first_win} to {last_win} ms, best at {} ms",
+                self.winning_count, self.optimal_hold_time
+            ),
+            None => write!(
+                f,
+                "no winning hold times, best at {} ms",
+                self.optimal_hold_time
+            ),
+        }
+    }
+}
+
+impl RaceModel {
+    // An uncapped charge that overflows has no u128 speed; a capped one is just at the cap
+    fn speed(&self, hold_time: u128) -> Option<u128> {
+        let speed = hold_time
+            .checked_pow(self.charge_exponent)
+            .and_then(|charge| self.acceleration.checked_mul(charge));
+        match (speed, self.max_speed) {
+            (Some(speed), Some(max_speed)) => Some(u128::min(speed, max_speed)),
+            (None, Some(max_speed)) => Some(max_speed),
+            (speed, None) => speed,
+        }
+    }
+
+    // Distances are exact or an error, never saturated, so comparisons between them hold
+    fn distance(&self, hold_time: u128, time: u128) -> Result<u128, String> {
+        self.speed(hold_time)
+            .and_then(|speed| speed.checked_mul(time - hold_time))
+            .ok_or(format!(
+                "distance for holding {hold_time} ms of a {time} ms race overflows u128"
+            ))
+    }
+
+    // Distance rises then falls with hold time, so the first hold time that does no better
+    // than the next one is the optimum
+    fn optimal_hold_time(&self, time: u128) -> Result<u128, String> {
+        let (mut low, mut high) = (0, time);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.distance(mid, time)? < self.distance(mid + 1, time)? {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+
+    fn analyse(&self, time: u128, distance: u128) -> Result<RaceReport, String> {
+        let optimal_hold_time = self.optimal_hold_time(time)?;
+        let beats_record =
+            |hold_time| -> Result<bool, String> { Ok(self.distance(hold_time, time)? > distance) };
+        if !beats_record(optimal_hold_time)? {
+            return Ok(RaceReport {
+                winning_count: 0,
+                winning_range: None,
+                optimal_hold_time,
+            });
+        }
+
+        // The uncapped linear charge has a closed form: a*t*(T-t) > D iff t*(T-t) > D/a
+        let closed_form_count = match (self.max_speed, self.charge_exponent) {
+            (None, 1) if self.acceleration > 0 => {
+                count_record_strategies(time, distance / self.acceleration)
+            }
+            _ => None,
+        };
+        let (first_win, last_win) = match closed_form_count {
+            Some(count) => {
+                let first_win = (time + 1 - count) / 2;
+                (first_win, first_win + count - 1)
+            }
+            None => {
+                // Searches either side of the optimum, where distance is monotonic
+                let (mut low, mut high) = (0, optimal_hold_time);
+                while low < high {
+                    let mid = low + (high - low) / 2;
+                    if beats_record(mid)? {
+                        high = mid;
+                    } else {
+                        low = mid + 1;
+                    }
+                }
+                let first_win = low;
+                let (mut low, mut high) = (optimal_hold_time, time);
+                while low < high {
+                    let mid = low + (high - low).div_ceil(2);
+                    if beats_record(mid)? {
+                        low = mid;
+                    } else {
+                        high = mid - 1;
+                    }
+                }
+                (first_win, low)
+            }
+        };
+
+        Ok(RaceReport {
+            winning_count: last_win - first_win + 1,
+            winning_range: Some((first_win, last_win)),
+            optimal_hold_time,
+        })
+    }
+}
+
+fn parse_race_model<I: Iterator<Item = String>>(args: I) -> Result<RaceModel, String> {
+    let mut race_model = BOAT_RACE;
+    for arg in args {
+        let (flag, value) = arg
+            .split_once('=')
+            .ok_or(format!("Expected --<setting>=<value>, got: {arg}"))?;
+        match flag {
+            "--acceleration" => {
+                race_model.acceleration = value
+                    .parse()
+                    .map_err(|err| format!("Invalid acceleration: {err}"))?
+            }
+            "--max-speed" => {
+                race_model.max_speed = Some(
+                    value
+                        .parse()
+                        .map_err(|err| format!("Invalid max speed: {err}"))?,
+                )
+            }
+            "--exponent" => {
+                race_model.charge_exponent = value
+                    .parse()
+                    .map_err(|err| format!("Invalid charge exponent: {err}"))?
+            }
+            _ => return Err(format!("Unknown setting: {flag}")),
+        }
+    }
+    Ok(race_model)
+}
+
+fn beats_record(hold_time: u128, time: u128, distance: u128) -> bool {
+    hold_time * (time - hold_time) > distance
+}
+
+// Hold times beating the record lie strictly between the roots of t^2 - time*t + distance, so
+// the integer square root of the discriminant lands within a step of the first winning time.
+// A tied record (an exact root) does not count as a win. None if time^2 overflows u128.
+fn count_record_strategies(time: u128, distance: u128) -> Option<u128> {
+    let discriminant = time
+        .checked_mul(time)?
+        .checked_sub(distance.checked_mul(4)?);
+    let Some(discriminant) = discriminant else {
+        return Some(0);
+    };
+
+    let mut first_win = (time - discriminant.isqrt()) / 2;
+    while first_win <= time / 2 && !beats_record(first_win, time, distance) {
+        first_win += 1;
+    }
+    while first_win > 0 && beats_record(first_win - 1, time, distance) {
+        first_win -= 1;
+    }
+
+    if first_win > time / 2 {
+        Some(0)
+    } else {
+        Some(time - 2 * first_win + 1)
+    }
+}
+
+#[derive(Debug)]
+struct RaceSheet {
+    races: Vec<(u128, u128)>,
+    // The digits of each line with the spacing removed, for the kerning-joined reading
+    kerned_time: String,
+    kerned_distance: String,
+}
+
+fn parse_sheet_line(
+    line: Option<(usize, &str)>,
+    label: &str,
+) -> Result<(Vec<u128>, String), ParseFileError> {
+    let (line_number, line) =
+        line.ok_or(ParseFileError::from_str(format!("missing '{label}' line")))?;
+    let values = line
+        .strip_prefix(label)
+        .ok_or(ParseFileError::from_str(format!(
+            "line {line_number}: expected '{label}'"
+        )))?
         .split_whitespace()
-        .filter_map(|s| s.parse::<u64>().ok());
+        .collect::<Vec<&str>>();
+    let parsed_values = values
+        .iter()
+        .map(|value| {
+            value.parse::<u128>().map_err(|err| {
+                ParseFileError::from_str(format!(
+                    "line {line_number}: invalid value '{value}': {err}"
+                ))
+            })
+        })
+        .collect::<Result<Vec<u128>, ParseFileError>>()?;
+    Ok((parsed_values, values.concat()))
+}
+
+impl FromStr for RaceSheet {
+    type Err = ParseFileError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut file_lines = s
+            .lines()
+            .enumerate()
+            .map(|(line_index, line)| (line_index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+        let (times, kerned_time) = parse_sheet_line(file_lines.next(), "Time:")?;
+        let (distances, kerned_distance) = parse_sheet_line(file_lines.next(), "Distance:")?;
 
-    let races_iter = times_iter.zip(dist_iter);
-    races_iter.map(|(t, d)| count_record_strategies(t, d)).product()
+        if let Some((line_number, _)) = file_lines.next() {
+            return Err(ParseFileError::from_str(format!(
+                "line {line_number}: unexpected line after 'Distance:'"
+            )));
+        }
+        if times.len() != distances.len() {
+            return Err(ParseFileError::from_str(format!(
+                "{} times but {} distances",
+                times.len(),
+                distances.len()
+            )));
+        }
+        if times.is_empty() {
+            return Err(ParseFileError::from_str(String::from("no races")));
+        }
+
+        Ok(RaceSheet {
+            races: times.into_iter().zip(distances).collect(),
+            kerned_time,
+            kerned_distance,
+        })
+    }
+}
+
+impl RaceSheet {
+    // Each column is its own race
+    fn races(&self) -> &[(u128, u128)] {
+        &self.races
+    }
+
+    // All columns read as one race, ignoring the spaces between them
+    fn kerned_race(&self) -> Result<(u128, u128), ParseFileError> {
+        let time = self
+            .kerned_time
+            .parse::<u128>()
+            .map_err(ParseFileError::from_err)?;
+        let distance = self
+            .kerned_distance
+            .parse::<u128>()
+            .map_err(ParseFileError::from_err)?;
+        Ok((time, distance))
+    }
 }
 
-fn part_two(file_contents: &str) -> u64 {
-    let mut file_lines = file_contents.lines();
-    
-    let time = file_lines
-        .next()
-        .expect("Unable to read line")
-        .chars()
-        .filter(|c| c.is_digit(10))
-        .collect::<String>()
-        .parse::<u64>().expect("Unable to parse line into time value");
-    let dist = file_lines
-        .next()
-        .expect("Unable to read line")
-        .chars()
-        .filter(|c| c.is_digit(10))
-        .collect::<String>()
-        .parse::<u64>().expect("Unable to parse line into dist value");
+fn part_one(race_sheet: &RaceSheet, race_model: &RaceModel) -> Result<Vec<RaceReport>, String> {
+    race_sheet
+        .races()
+        .iter()
+        .map(|&(t, d)| race_model.analyse(t, d))
+        .collect()
+}
 
-    count_record_strategies(time, dist)
+fn part_two(race_sheet: &RaceSheet, race_model: &RaceModel) -> Result<RaceReport, String> {
+    let (time, distance) = race_sheet.kerned_race().map_err(|err| err.to_string())?;
+    race_model.analyse(time, distance)
 }
 
 fn main() {
     let file_path = "input.txt";
     let file_contents = fs::read_to_string(file_path).expect("Unable to read file: {file_path}");
-    
-    let part_one_record_product = part_one(&file_contents);
-    println!("Part One -- Product of Record Stragies: {}", part_one_record_product);
 
-    let part_two_record = part_two(&file_contents);
-    println!("Part Two -- Record Strategies: {}", part_two_record);
+    let race_sheet = match file_contents.parse::<RaceSheet>() {
+        Ok(race_sheet) => race_sheet,
+        Err(err) => {
+            println!("Error parsing file contents: {err}");
+            return;
+        }
+    };
+
+    let race_model = match parse_race_model(env::args().skip(1)) {
+        Ok(race_model) => race_model,
+        Err(err) => {
+            println!("Error parsing race model: {err}");
+            return;
+        }
+    };
+
+    let part_one_reports = match part_one(&race_sheet, &race_model) {
+        Ok(part_one_reports) => part_one_reports,
+        Err(err) => {
+            println!("Error analysing races: {err}");
+            return;
+        }
+    };
+    for race_report in &part_one_reports {
+        println!("  {race_report}");
+    }
+    let part_one_record_product = part_one_reports
+        .iter()
+        .try_fold(1u128, |product, race_report| {
+            product.checked_mul(race_report.winning_count)
+        });
+    match part_one_record_product {
+        Some(product) => println!("Part One -- Product of Record Stragies: {product}"),
+        None => println!("Part One -- Product of Record Stragies overflows u128"),
+    }
+
+    let part_two_report = match part_two(&race_sheet, &race_model) {
+        Ok(part_two_report) => part_two_report,
+        Err(err) => {
+            println!("Error analysing kerned race: {err}");
+            return;
+        }
+    };
+    println!("  {part_two_report}");
+    println!(
+        "Part Two -- Record Strategies: {}",
+        part_two_report.winning_count
+    );
 }
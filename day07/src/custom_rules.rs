@@ -0,0 +1,78 @@
+use super::Ruleset;
+use std::{collections::HashMap, str::FromStr};
+
+// Rules read from a file of `key=value` lines:
+//   cards=J23456789TQKA   every card in the deck, weakest first
+//   wild=J                optional, cards that join the most frequent card
+//   hand_size=7           optional, defaults to 5
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomRules {
+    card_values: HashMap<char, u32>,
+    wild_cards: Vec<char>,
+    hand_size: usize,
+}
+
+impl Ruleset for CustomRules {
+    fn card_value(&self, c: char) -> Option<u32> {
+        self.card_values.get(&c).copied()
+    }
+
+    fn is_wild(&self, c: char) -> bool {
+        self.wild_cards.contains(&c)
+    }
+
+    fn hand_size(&self) -> usize {
+        self.hand_size
+    }
+}
+
+impl FromStr for CustomRules {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut card_values = HashMap::new();
+        let mut wild_cards = Vec::new();
+        let mut hand_size = 5;
+
+        for (line, line_number) in s.lines().zip(1..) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(format!("line {line_number}: expected 'key=value'"))?;
+            match key.trim() {
+                "cards" => {
+                    for (c, card_value) in value.trim().chars().zip(1..) {
+                        if card_values.insert(c, card_value).is_some() {
+                            return Err(format!("line {line_number}: card {c} listed twice"))?;
+                        }
+                    }
+                }
+                "wild" => wild_cards.extend(value.trim().chars()),
+                "hand_size" => {
+                    hand_size = value.trim().parse().map_err(|err| {
+                        format!("line {line_number}: invalid hand size '{value}': {err}")
+                    })?
+                }
+                key => return Err(format!("line {line_number}: unknown key '{key}'"))?,
+            }
+        }
+
+        if card_values.is_empty() {
+            return Err("No cards listed")?;
+        }
+        if hand_size == 0 {
+            return Err("Hand size must be at least 1")?;
+        }
+        if let Some(c) = wild_cards.iter().find(|c| !card_values.contains_key(c)) {
+            return Err(format!("Wild card {c} is not in the deck"))?;
+        }
+        Ok(CustomRules {
+            card_values,
+            wild_cards,
+            hand_size,
+        })
+    }
+}
@@ -1,166 +1,183 @@
-use regex::Regex;
+mod custom_rules;
+mod poker;
+mod tournament;
+
+use custom_rules::CustomRules;
+use poker::PokerHand;
 use std::{
     cmp::{self, Ordering},
     collections::HashMap,
-    fs,
+    env, fmt, fs,
+    marker::PhantomData,
     str::FromStr,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
-    HighCard,
-    OnePair,
-    TwoPair,
-    ThreeOfAKind,
-    FullHouse,
-    FourOfAKind,
-    FiveOfAKind,
+// Card counts from the largest group down, e.g. [3, 2] for a full house. Comparing profiles
+// lexicographically orders hand types for any hand size.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct HandType(Vec<u32>);
+
+impl HandType {
+    fn from_counts(counts: impl IntoIterator<Item = u32>, wild_count: u32) -> Self {
+        let mut profile = counts.into_iter().collect::<Vec<u32>>();
+        profile.sort_by_key(|&count| cmp::Reverse(count));
+        // Add wild cards to the most frequent card
+        if let Some(first) = profile.first_mut() {
+            *first += wild_count;
+        } else {
+            profile.push(wild_count);
+        }
+        HandType(profile)
+    }
+}
+
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.as_slice() {
+            [5] => write!(f, "FiveOfAKind"),
+            [4, 1] => write!(f, "FourOfAKind"),
+            [3, 2] => write!(f, "FullHouse"),
+            [3, 1, 1] => write!(f, "ThreeOfAKind"),
+            [2, 2, 1] => write!(f, "TwoPair"),
+            [2, 1, 1, 1] => write!(f, "OnePair"),
+            [1, 1, 1, 1, 1] => write!(f, "HighCard"),
+            profile => write!(
+                f,
+                "{}",
+                profile
+                    .iter()
+                    .map(|count| count.to_string())
+                    .collect::<Vec<String>>()
+                    .join("+")
+            ),
+        }
+    }
 }
 
 trait CamelCardHandGeneric:
-    Clone + Copy + PartialEq + Eq + PartialOrd + FromStr<Err = Box<dyn std::error::Error>>
+    Clone + PartialEq + Eq + PartialOrd + Ord + FromStr<Err = Box<dyn std::error::Error>>
 {
 }
 
-impl CamelCardHandGeneric for CamelCardHand {}
+// The parts of the Camel Cards rules that vary between games
+trait Ruleset: fmt::Debug + Clone + PartialEq + Eq {
+    // Rank of a card for tie-breaking, or None if the card isn't in the deck
+    fn card_value(&self, c: char) -> Option<u32>;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct CamelCardHand {
-    hand: [u32; 5],
-    hand_type: HandType,
-}
+    // Wild cards join whichever card is most frequent when classifying the hand
+    fn is_wild(&self, _c: char) -> bool {
+        false
+    }
 
-impl FromStr for CamelCardHand {
-    type Err = Box<dyn std::error::Error>;
+    fn hand_size(&self) -> usize {
+        5
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // let mut letter_counts: HashMap<char, usize> = HashMap::new();
-        let hand_vec = s
-            .chars()
-            .map(|c| match c {
-                'A' => Ok(14),
-                'K' => Ok(13),
-                'Q' => Ok(12),
-                'J' => Ok(11),
-                'T' => Ok(10),
-                _ => c.to_digit(10).ok_or("No matching card type"),
-            })
-            .collect::<Result<Vec<u32>, _>>()?;
+    // Hands of the same type are compared on this key, by default card by card in hand order
+    fn tie_break_key(&self, cards: Vec<u32>) -> Vec<u32> {
+        cards
+    }
+}
 
-        let letter_counts: HashMap<char, u32> = s.chars().fold(HashMap::new(), |mut map, c| {
-            *map.entry(c).or_insert(0) += 1;
-            map
-        });
+fn face_card_value(c: char, jack_value: u32) -> Option<u32> {
+    match c {
+        'A' => Some(14),
+        'K' => Some(13),
+        'Q' => Some(12),
+        'J' => Some(jack_value),
+        'T' => Some(10),
+        '2'..='9' => c.to_digit(10),
+        _ => None,
+    }
+}
 
-        let mut sorted_letter_freqs = letter_counts.into_values().collect::<Vec<u32>>();
-        sorted_letter_freqs.sort_by_key(|&v| cmp::Reverse(v));
-        let hand_type = match (sorted_letter_freqs.get(0), sorted_letter_freqs.get(1)) {
-            (Some(5), _) => HandType::FiveOfAKind,
-            (Some(4), _) => HandType::FourOfAKind,
-            (Some(3), Some(2)) => HandType::FullHouse,
-            (Some(3), _) => HandType::ThreeOfAKind,
-            (Some(2), Some(2)) => HandType::TwoPair,
-            (Some(2), _) => HandType::OnePair,
-            (Some(1), _) => HandType::HighCard,
-            _ => return Err("No matching hand type")?,
-        };
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct StandardRules;
 
-        match hand_vec.try_into() {
-            Ok(hand) => Ok(CamelCardHand {
-                hand: hand,
-                hand_type: hand_type,
-            }),
-            Err(e) => Err("Unable to convert input to Camel Hand")?,
-        }
+impl Ruleset for StandardRules {
+    fn card_value(&self, c: char) -> Option<u32> {
+        face_card_value(c, 11)
     }
 }
 
-impl Ord for CamelCardHand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (&self.hand_type, self.hand).cmp(&(&other.hand_type, other.hand))
+// J is a joker: wild, but the weakest card when breaking ties
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct JokerRules;
+
+impl Ruleset for JokerRules {
+    fn card_value(&self, c: char) -> Option<u32> {
+        face_card_value(c, 1)
     }
-}
 
-impl PartialOrd for CamelCardHand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    fn is_wild(&self, c: char) -> bool {
+        c == 'J'
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct CamelCardHandWithJokers {
-    hand: [u32; 5],
+// Only hands scored under the same rules instance should be compared
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hand<R: Ruleset> {
     hand_type: HandType,
+    tie_break_key: Vec<u32>,
+    ruleset: PhantomData<R>,
 }
 
-impl CamelCardHandGeneric for CamelCardHandWithJokers {}
+type CamelCardHand = Hand<StandardRules>;
+type CamelCardHandWithJokers = Hand<JokerRules>;
 
-impl FromStr for CamelCardHandWithJokers {
-    type Err = Box<dyn std::error::Error>;
+impl<R: Ruleset + Default> CamelCardHandGeneric for Hand<R> {}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // let mut letter_counts: HashMap<char, usize> = HashMap::new();
+impl<R: Ruleset> Hand<R> {
+    fn parse_with(s: &str, rules: &R) -> Result<Self, Box<dyn std::error::Error>> {
         let hand_vec = s
             .chars()
-            .map(|c| match c {
-                'A' => Ok(14),
-                'K' => Ok(13),
-                'Q' => Ok(12),
-                'J' => Ok(1),
-                'T' => Ok(10),
-                _ => c.to_digit(10).ok_or("No matching card type"),
+            .map(|c| {
+                rules
+                    .card_value(c)
+                    .ok_or(format!("No matching card type: {c}"))
             })
             .collect::<Result<Vec<u32>, _>>()?;
+        if hand_vec.len() != rules.hand_size() {
+            return Err(format!(
+                "Expected {} cards in hand, found {}",
+                rules.hand_size(),
+                hand_vec.len()
+            ))?;
+        }
 
-        let mut letter_counts: HashMap<char, u32> = s.chars().fold(HashMap::new(), |mut map, c| {
-            *map.entry(c).or_insert(0) += 1;
+        let mut wild_count = 0;
+        let letter_counts: HashMap<char, u32> = s.chars().fold(HashMap::new(), |mut map, c| {
+            if rules.is_wild(c) {
+                wild_count += 1;
+            } else {
+                *map.entry(c).or_insert(0) += 1;
+            }
             map
         });
 
-        let joker_count = if let Some(count) = letter_counts.remove(&'J') {
-            count.to_owned()
-        } else {
-            0
-        };
-
-        let mut sorted_letter_freqs = letter_counts.into_values().collect::<Vec<u32>>();
-        sorted_letter_freqs.sort_by_key(|&v| cmp::Reverse(v));
-        // Add jokers to the most frequent number
-        // sorted_letter_freqs[0] += joker_count;
-        if let Some(first) = sorted_letter_freqs.first_mut() {
-            *first += joker_count;
-        } else {
-            sorted_letter_freqs.push(joker_count);
-        }
+        Ok(Hand {
+            hand_type: HandType::from_counts(letter_counts.into_values(), wild_count),
+            tie_break_key: rules.tie_break_key(hand_vec),
+            ruleset: PhantomData,
+        })
+    }
+}
 
-        let hand_type = match (sorted_letter_freqs.get(0), sorted_letter_freqs.get(1)) {
-            (Some(5), _) => HandType::FiveOfAKind,
-            (Some(4), _) => HandType::FourOfAKind,
-            (Some(3), Some(2)) => HandType::FullHouse,
-            (Some(3), _) => HandType::ThreeOfAKind,
-            (Some(2), Some(2)) => HandType::TwoPair,
-            (Some(2), _) => HandType::OnePair,
-            (Some(1), _) => HandType::HighCard,
-            _ => return Err("No matching hand type")?,
-        };
+impl<R: Ruleset + Default> FromStr for Hand<R> {
+    type Err = Box<dyn std::error::Error>;
 
-        match hand_vec.try_into() {
-            Ok(hand) => Ok(CamelCardHandWithJokers {
-                hand: hand,
-                hand_type: hand_type,
-            }),
-            Err(e) => Err("Unable to convert input to Camel Hand")?,
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Hand::parse_with(s, &R::default())
     }
 }
 
-impl Ord for CamelCardHandWithJokers {
+impl<R: Ruleset> Ord for Hand<R> {
     fn cmp(&self, other: &Self) -> Ordering {
-        (&self.hand_type, self.hand).cmp(&(&other.hand_type, other.hand))
+        (&self.hand_type, &self.tie_break_key).cmp(&(&other.hand_type, &other.tie_break_key))
     }
 }
 
-impl PartialOrd for CamelCardHandWithJokers {
+impl<R: Ruleset> PartialOrd for Hand<R> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
@@ -168,45 +185,84 @@ impl PartialOrd for CamelCardHandWithJokers {
 
 fn parse_file_contents<H: CamelCardHandGeneric>(
     file_contents: &str,
+) -> Result<Vec<(H, u32)>, Box<dyn std::error::Error>> {
+    parse_file_contents_with(file_contents, |s| s.parse::<H>())
+}
+
+fn parse_file_contents_with<H>(
+    file_contents: &str,
+    parse_hand: impl Fn(&str) -> Result<H, Box<dyn std::error::Error>>,
 ) -> Result<Vec<(H, u32)>, Box<dyn std::error::Error>> {
     file_contents
         .lines()
         .map(|line| {
             let mut line_split = line.split_whitespace();
-            let hand = line_split.next().ok_or("No Camel Hand")?.parse::<H>()?;
+            let hand = parse_hand(line_split.next().ok_or("No Camel Hand")?)?;
             let bid = line_split.next().ok_or("No bid")?.parse::<u32>()?;
             Ok((hand, bid))
         })
         .collect::<Result<Vec<(_, _)>, Box<dyn std::error::Error>>>()
 }
 
-fn part_one(hands_with_bids: &Vec<(CamelCardHand, u32)>) -> u32 {
-    let mut hands_with_bids_sorted: Vec<(CamelCardHand, u32)> = hands_with_bids.to_vec();
-    hands_with_bids_sorted.sort_by_key(|&(hand, _)| hand);
-    // println!("Hands with bids sorted: {hands_with_bids_sorted:?}");
+fn total_winnings<H: Ord + Clone>(hands_with_bids: &[(H, u32)]) -> u32 {
+    let mut hands_with_bids_sorted: Vec<(H, u32)> = hands_with_bids.to_vec();
+    hands_with_bids_sorted.sort_by(|(a, _), (b, _)| a.cmp(b));
     hands_with_bids_sorted
         .into_iter()
         .zip(1..)
-        .map(|((hand, bid), rank)| bid * rank)
+        .map(|((_, bid), rank)| bid * rank)
         .sum()
 }
 
-fn part_two(hands_with_bids_and_jokers: &Vec<(CamelCardHandWithJokers, u32)>) -> u32 {
-    let mut hands_with_bids_sorted: Vec<(CamelCardHandWithJokers, u32)> =
-        hands_with_bids_and_jokers.to_vec();
-    hands_with_bids_sorted.sort_by_key(|&(hand, _)| hand);
-    // println!("Hands with bids sorted: {hands_with_bids_sorted:?}");
-    hands_with_bids_sorted
-        .into_iter()
-        .zip(1..)
-        .map(|((hand, bid), rank)| bid * rank)
-        .sum()
+fn part_one(hands_with_bids: &[(CamelCardHand, u32)]) -> u32 {
+    total_winnings(hands_with_bids)
+}
+
+fn part_two(hands_with_bids_and_jokers: &[(CamelCardHandWithJokers, u32)]) -> u32 {
+    total_winnings(hands_with_bids_and_jokers)
 }
 
 fn main() {
     let file_path = "input.txt";
     let file_contents = fs::read_to_string(file_path).expect("Unable to read file: {file_path}");
 
+    // `day07 --poker` scores the same file as suited poker hands instead
+    if env::args().nth(1).as_deref() == Some("--poker") {
+        match parse_file_contents::<PokerHand>(&file_contents) {
+            Ok(poker_hands_with_bids) => println!(
+                "Poker -- Total Winnings: {}",
+                total_winnings(&poker_hands_with_bids)
+            ),
+            Err(err) => println!("Unable to parse file contents: {err}"),
+        }
+        return;
+    }
+
+    // `day07 --rules=<file>` scores the file with a card alphabet and hand size read from <file>
+    if let Some(rules_path) = env::args().nth(1).and_then(|arg| {
+        arg.strip_prefix("--rules=")
+            .map(|rules_path| rules_path.to_string())
+    }) {
+        let rules = match fs::read_to_string(&rules_path)
+            .map_err(|err| err.into())
+            .and_then(|rules_contents| rules_contents.parse::<CustomRules>())
+        {
+            Ok(rules) => rules,
+            Err(err) => {
+                println!("Unable to read rules from {rules_path}: {err}");
+                return;
+            }
+        };
+        match parse_file_contents_with(&file_contents, |s| Hand::parse_with(s, &rules)) {
+            Ok(hands_with_bids) => println!(
+                "Custom Rules -- Total Winnings: {}",
+                total_winnings(&hands_with_bids)
+            ),
+            Err(err) => println!("Unable to parse file contents: {err}"),
+        }
+        return;
+    }
+
     let hands_with_bids = match parse_file_contents(&file_contents) {
         Ok(parsed) => parsed,
         Err(err) => {
@@ -228,4 +284,77 @@ fn main() {
 
     let part_two_total_winnings = part_two(&hands_with_bids_and_jokers);
     println!("Part Two -- Total Winnings: {part_two_total_winnings}");
+
+    // `day07 --stats [seed]` analyses the bid list under both rule sets
+    if env::args().nth(1).as_deref() == Some("--stats") {
+        let seed = env::args()
+            .nth(2)
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or(2023);
+        print_statistics(&hands_with_bids, &hands_with_bids_and_jokers, seed);
+    }
+}
+
+const MONTE_CARLO_SAMPLES: usize = 10_000;
+const CARD_ALPHABET: [char; 13] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+];
+
+fn print_statistics(
+    hands_with_bids: &[(CamelCardHand, u32)],
+    hands_with_bids_and_jokers: &[(CamelCardHandWithJokers, u32)],
+    seed: u64,
+) {
+    println!("Hand Types -- Without Jokers / With Jokers:");
+    let distribution = tournament::hand_type_distribution(hands_with_bids);
+    let joker_distribution = tournament::hand_type_distribution(hands_with_bids_and_jokers);
+    let mut hand_types = distribution
+        .keys()
+        .chain(joker_distribution.keys())
+        .cloned()
+        .collect::<Vec<HandType>>();
+    hand_types.sort();
+    hand_types.dedup();
+    for hand_type in hand_types {
+        println!(
+            "  {hand_type}: {} / {}",
+            distribution.get(&hand_type).unwrap_or(&0),
+            joker_distribution.get(&hand_type).unwrap_or(&0)
+        );
+    }
+
+    let rank_changes = tournament::rank_changes(hands_with_bids, hands_with_bids_and_jokers);
+    let mut biggest_movers = rank_changes.iter().enumerate().collect::<Vec<_>>();
+    biggest_movers.sort_by_key(|&(_, change)| cmp::Reverse(change.abs()));
+    println!(
+        "Rank Changes With Jokers -- {} up, {} down, {} unchanged",
+        rank_changes.iter().filter(|&&change| change > 0).count(),
+        rank_changes.iter().filter(|&&change| change < 0).count(),
+        rank_changes.iter().filter(|&&change| change == 0).count()
+    );
+    for (hand_index, change) in biggest_movers.into_iter().take(5) {
+        println!("  Line {}: {change:+}", hand_index + 1);
+    }
+
+    let mut rng = tournament::Rng::new(seed);
+    let expected_winnings = tournament::expected_winnings_per_bid(
+        hands_with_bids,
+        &CARD_ALPHABET,
+        5,
+        MONTE_CARLO_SAMPLES,
+        &mut rng,
+    );
+    let joker_expected_winnings = tournament::expected_winnings_per_bid(
+        hands_with_bids_and_jokers,
+        &CARD_ALPHABET,
+        5,
+        MONTE_CARLO_SAMPLES,
+        &mut rng,
+    );
+    match (expected_winnings, joker_expected_winnings) {
+        (Ok(expected_winnings), Ok(joker_expected_winnings)) => println!(
+            "Expected Winnings per Bid of a Random Hand -- {expected_winnings:.2} / {joker_expected_winnings:.2}"
+        ),
+        (Err(err), _) | (_, Err(err)) => println!("Unable to simulate random hands: {err}"),
+    }
 }
@@ -0,0 +1,197 @@
+use super::CamelCardHandGeneric;
+use std::{cmp, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PokerCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+// A poker hand is scored by its best five cards. Hands of the same category are compared by
+// rank, largest group first (e.g. the pair, then the kickers), never by card position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PokerHand {
+    category: PokerCategory,
+    ranks: [u32; 5],
+}
+
+impl CamelCardHandGeneric for PokerHand {}
+
+fn rank_value(c: char) -> Option<u32> {
+    match c {
+        'A' => Some(14),
+        'K' => Some(13),
+        'Q' => Some(12),
+        'J' => Some(11),
+        'T' => Some(10),
+        '2'..='9' => c.to_digit(10),
+        _ => None,
+    }
+}
+
+fn evaluate_five(cards: [(u32, char); 5]) -> PokerHand {
+    let is_flush = cards.iter().all(|&(_, suit)| suit == cards[0].1);
+
+    // (count, rank), largest group first and then highest rank first
+    let mut groups: Vec<(u32, u32)> = Vec::new();
+    for &(rank, _) in &cards {
+        match groups
+            .iter_mut()
+            .find(|(_, group_rank)| *group_rank == rank)
+        {
+            Some((count, _)) => *count += 1,
+            None => groups.push((1, rank)),
+        }
+    }
+    groups.sort_by_key(|&group| cmp::Reverse(group));
+
+    let mut ranks = [0; 5];
+    for (slot, &(_, rank)) in ranks.iter_mut().zip(&groups) {
+        *slot = rank;
+    }
+
+    // Five distinct ranks spanning four, or the ace-low wheel where the five is high
+    let straight_high = if groups.len() == 5 && ranks[0] - ranks[4] == 4 {
+        Some(ranks[0])
+    } else if ranks == [14, 5, 4, 3, 2] {
+        Some(5)
+    } else {
+        None
+    };
+
+    let category = match (straight_high, is_flush, groups[0].0, groups.get(1)) {
+        (Some(_), true, _, _) => PokerCategory::StraightFlush,
+        (_, _, 4, _) => PokerCategory::FourOfAKind,
+        (_, _, 3, Some((2, _))) => PokerCategory::FullHouse,
+        (_, true, _, _) => PokerCategory::Flush,
+        (Some(_), _, _, _) => PokerCategory::Straight,
+        (_, _, 3, _) => PokerCategory::ThreeOfAKind,
+        (_, _, 2, Some((2, _))) => PokerCategory::TwoPair,
+        (_, _, 2, _) => PokerCategory::OnePair,
+        _ => PokerCategory::HighCard,
+    };
+    if let Some(high) = straight_high {
+        ranks = [high, 0, 0, 0, 0];
+    }
+
+    PokerHand { category, ranks }
+}
+
+// Every five card subset of a hand of five or more cards
+fn five_card_subsets(cards: &[(u32, char)]) -> Vec<[(u32, char); 5]> {
+    let n = cards.len();
+    let mut subsets = Vec::new();
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                for d in c + 1..n {
+                    for e in d + 1..n {
+                        subsets.push([cards[a], cards[b], cards[c], cards[d], cards[e]]);
+                    }
+                }
+            }
+        }
+    }
+    subsets
+}
+
+// Cards are written as rank then suit, e.g. `AsKhTd9c2s`; hands of more than five cards
+// (such as seven card stud) are scored by their best five
+impl FromStr for PokerHand {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars = s.chars().collect::<Vec<char>>();
+        if chars.len() % 2 != 0 || chars.len() < 10 {
+            return Err("Poker hands need at least five rank-suit pairs")?;
+        }
+        let cards = chars
+            .chunks(2)
+            .map(|card| {
+                let rank = rank_value(card[0]).ok_or("No matching card rank")?;
+                match card[1].to_ascii_lowercase() {
+                    suit @ ('c' | 'd' | 'h' | 's') => Ok((rank, suit)),
+                    _ => Err("No matching card suit"),
+                }
+            })
+            .collect::<Result<Vec<(u32, char)>, _>>()?;
+        for (i, card) in cards.iter().enumerate() {
+            if cards[i + 1..].contains(card) {
+                return Err("Card appears twice in hand")?;
+            }
+        }
+
+        five_card_subsets(&cards)
+            .into_iter()
+            .map(evaluate_five)
+            .max()
+            .ok_or("No five card hand".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PokerHand;
+
+    fn hand(s: &str) -> PokerHand {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn categories_rank_from_straight_flush_to_high_card() {
+        let hands = [
+            "9h8h7h6h5h",
+            "AsAhAdAc2s",
+            "KsKhKd2c2s",
+            "Ah9h7h4h2h",
+            "9s8h7d6c5s",
+            "QsQhQd4c2s",
+            "JsJh9d9c2s",
+            "TsTh8d4c2s",
+            "As9h7d4c2s",
+        ];
+        for pair in hands.windows(2) {
+            assert!(hand(pair[0]) > hand(pair[1]), "{} > {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn wheel_ranks_below_six_high_straight() {
+        assert!(hand("Ah2s3d4c5h") < hand("2h3s4d5c6h"));
+        assert!(hand("Ah2s3d4c5h") > hand("AsAhKdQcJs"));
+    }
+
+    #[test]
+    fn kickers_break_ties() {
+        assert!(hand("AsAh9d5c2s") > hand("AdAc9h4s2h"));
+        assert!(hand("KsKh4d4c9s") > hand("KdKc4h4s8h"));
+        assert_eq!(hand("AsAh9d5c2s"), hand("AdAc9h5s2h"));
+    }
+
+    #[test]
+    fn flushes_compare_by_rank() {
+        assert!(hand("Ah9h7h4h2h") > hand("KsQsJs9s7s"));
+        assert!(hand("AsQs7s4s2s") > hand("Ah9h7h4h3h"));
+        assert!(hand("Ah9h7h4h3h") > hand("As9s7s4s2s"));
+    }
+
+    #[test]
+    fn seven_cards_use_best_five() {
+        assert_eq!(hand("AsKsQsJsTs2h3d"), hand("AhKhQhJhTh"));
+        assert_eq!(hand("2h2d2c9s9h3d4c"), hand("2h2d2c9s9h"));
+        assert!(hand("2h3h4h5h7h8c9d") > hand("8s9hTdJcQs"));
+    }
+
+    #[test]
+    fn duplicate_cards_are_rejected() {
+        assert!("AsAsKhQdJc".parse::<PokerHand>().is_err());
+        assert!("AsKhQdJcTs9h9h".parse::<PokerHand>().is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use super::{CamelCardHandGeneric, Hand, HandType, Ruleset};
+use std::collections::BTreeMap;
+
+pub fn hand_type_distribution<R: Ruleset>(
+    hands_with_bids: &[(Hand<R>, u32)],
+) -> BTreeMap<HandType, usize> {
+    let mut distribution = BTreeMap::new();
+    for (hand, _) in hands_with_bids {
+        *distribution.entry(hand.hand_type.clone()).or_insert(0) += 1;
+    }
+    distribution
+}
+
+// Rank (1 = weakest) of each hand, in input order
+pub fn ranks<H: CamelCardHandGeneric>(hands_with_bids: &[(H, u32)]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..hands_with_bids.len()).collect();
+    order.sort_by(|&a, &b| hands_with_bids[a].0.cmp(&hands_with_bids[b].0));
+    let mut ranks = vec![0; hands_with_bids.len()];
+    for (hand_index, rank) in order.into_iter().zip(1..) {
+        ranks[hand_index] = rank;
+    }
+    ranks
+}
+
+// How far each hand moves when switching rule sets, in input order; both lists must come from
+// the same file
+pub fn rank_changes<A: CamelCardHandGeneric, B: CamelCardHandGeneric>(
+    before: &[(A, u32)],
+    after: &[(B, u32)],
+) -> Vec<i64> {
+    ranks(before)
+        .into_iter()
+        .zip(ranks(after))
+        .map(|(before_rank, after_rank)| after_rank as i64 - before_rank as i64)
+        .collect()
+}
+
+// xorshift64, so simulations are repeatable for a given seed without extra dependencies
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed.max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+// Deals `samples` random hands from `alphabet` and ranks each against the whole field, so the
+// result is the expected winnings of one unit of bid for a random entrant
+pub fn expected_winnings_per_bid<H: CamelCardHandGeneric>(
+    hands_with_bids: &[(H, u32)],
+    alphabet: &[char],
+    hand_size: usize,
+    samples: usize,
+    rng: &mut Rng,
+) -> Result<f64, Box<dyn std::error::Error>> {
+    let mut sorted_hands: Vec<H> = hands_with_bids
+        .iter()
+        .map(|(hand, _)| hand.clone())
+        .collect();
+    sorted_hands.sort();
+
+    let mut total_rank = 0usize;
+    for _ in 0..samples {
+        let dealt = (0..hand_size)
+            .map(|_| alphabet[rng.below(alphabet.len())])
+            .collect::<String>();
+        let hand = dealt.parse::<H>()?;
+        // Ties are ranked below the existing hand
+        total_rank += sorted_hands.partition_point(|other| *other < hand) + 1;
+    }
+    Ok(total_rank as f64 / samples as f64)
+}
@@ -0,0 +1,180 @@
+use super::{Instruction, Network};
+use num::Integer;
+use std::collections::{hash_map::Entry, HashMap};
+
+// A walk from one start node, as a lead-in of `offset` steps followed by a cycle of
+// `cycle_length` steps repeating forever. A walker is at an end node at step `t` when `t` is in
+// `end_hits`, or `t >= offset` and `offset + (t - offset) % cycle_length` is.
+#[derive(Debug)]
+pub struct GhostCycle {
+    pub offset: u64,
+    pub cycle_length: u64,
+    pub end_hits: Vec<u64>,
+}
+
+impl GhostCycle {
+    // The walk state is (node, position in the instructions), so it must repeat within
+    // `nodes * instructions` steps. Only the states the walk visits are recorded.
+    pub fn find(
+        start_node: u32,
+        is_end: &[bool],
+        instructions: &[Instruction],
+        network: &Network,
+    ) -> Self {
+        let instruction_length = instructions.len();
+        let mut first_seen: HashMap<(u32, usize), u64> = HashMap::new();
+        let mut end_hits = Vec::new();
+        let mut current_node = start_node;
+
+        for step in 0u64.. {
+            let position = step as usize % instruction_length;
+            match first_seen.entry((current_node, position)) {
+                Entry::Occupied(seen) => {
+                    return GhostCycle {
+                        offset: *seen.get(),
+                        cycle_length: step - seen.get(),
+                        end_hits,
+                    };
+                }
+                Entry::Vacant(unseen) => {
+                    unseen.insert(step);
+                }
+            }
+            if is_end[current_node as usize] {
+                end_hits.push(step);
+            }
+            current_node = network.step(current_node, instructions[position]);
+        }
+        unreachable!("Walk state space is finite")
+    }
+
+    fn is_end_at(&self, step: u128) -> bool {
+        let (offset, cycle_length) = (self.offset as u128, self.cycle_length as u128);
+        let step = if step < offset + cycle_length {
+            step
+        } else {
+            offset + (step - offset) % cycle_length
+        };
+        self.end_hits.binary_search(&(step as u64)).is_ok()
+    }
+
+    // Steps at which the walker is on an end node once inside the cycle, as residues
+    fn cycle_residues(&self) -> Vec<u64> {
+        self.end_hits
+            .iter()
+            .filter(|&&step| step >= self.offset)
+            .map(|&step| step % self.cycle_length)
+            .collect()
+    }
+}
+
+// Past this many residue classes, the remaining walkers are checked step by step instead
+const MAX_CONGRUENCES: usize = 1 << 12;
+
+fn overflow_error() -> String {
+    String::from("step count overflows an i128")
+}
+
+// Generalised Chinese Remainder Theorem for x = a1 (mod m1), x = a2 (mod m2) with moduli that
+// need not be coprime; None when the congruences contradict each other
+fn combine_congruences(
+    (a1, m1): (i128, i128),
+    (a2, m2): (i128, i128),
+) -> Result<Option<(i128, i128)>, String> {
+    let gcd = m1.extended_gcd(&m2);
+    if (a2 - a1) % gcd.gcd != 0 {
+        return Ok(None);
+    }
+    let lcm = (m1 / gcd.gcd).checked_mul(m2).ok_or_else(overflow_error)?;
+    // gcd.x * m1 = gcd (mod m2), so stepping a1 by multiples of m1 reaches a2's class
+    let reduced_modulus = m2 / gcd.gcd;
+    let multiple = ((a2 - a1) / gcd.gcd % reduced_modulus)
+        .checked_mul(gcd.x)
+        .ok_or_else(overflow_error)?
+        % reduced_modulus;
+    let residue = m1
+        .checked_mul(multiple)
+        .and_then(|offset| a1.checked_add(offset))
+        .ok_or_else(overflow_error)?;
+    Ok(Some((residue.rem_euclid(lcm), lcm)))
+}
+
+// The first step at which every walker is on an end node at once, or None if that never happens
+pub fn first_common_end(ghost_cycles: &[GhostCycle]) -> Result<Option<u128>, String> {
+    // Before every walker has entered its cycle, check steps one by one
+    let Some(lead_in) = ghost_cycles.iter().map(|ghost| ghost.offset).max() else {
+        return Ok(None);
+    };
+    if let Some(step) =
+        (0..lead_in as u128).find(|&step| ghost_cycles.iter().all(|ghost| ghost.is_end_at(step)))
+    {
+        return Ok(Some(step));
+    }
+
+    // After that each walker's end steps are residue classes, kept as (residue, modulus) pairs
+    let mut congruences = vec![(0i128, 1i128)];
+    let mut combined_count = 0;
+    for ghost in ghost_cycles {
+        let residues = ghost.cycle_residues();
+        if combined_count > 0 && congruences.len() * residues.len() > MAX_CONGRUENCES {
+            break;
+        }
+        let cycle_length = ghost.cycle_length as i128;
+        let mut combined = Vec::new();
+        for &congruence in &congruences {
+            for &residue in &residues {
+                if let Some(congruence) =
+                    combine_congruences(congruence, (residue as i128, cycle_length))?
+                {
+                    combined.push(congruence);
+                }
+            }
+        }
+        combined.sort();
+        combined.dedup();
+        congruences = combined;
+        combined_count += 1;
+        if congruences.is_empty() {
+            return Ok(None);
+        }
+    }
+
+    let lead_in = lead_in as i128;
+    let remaining = &ghost_cycles[combined_count..];
+    // Every walker combined: the smallest step from `lead_in` on in any of the surviving classes
+    if remaining.is_empty() {
+        return Ok(congruences
+            .into_iter()
+            .map(|(residue, modulus)| lead_in + (residue - lead_in).rem_euclid(modulus))
+            .min()
+            .map(|step| step as u128));
+    }
+
+    // Too many classes to enumerate: every class shares one modulus, so walk their steps in
+    // increasing order and check the remaining walkers at each. Past one period of all the
+    // cycles the pattern repeats, so the walk stops there if the period fits in an i128.
+    let modulus = congruences[0].1;
+    let walk_end = remaining
+        .iter()
+        .try_fold(modulus, |period, ghost| {
+            let cycle_length = ghost.cycle_length as i128;
+            (period / period.gcd(&cycle_length)).checked_mul(cycle_length)
+        })
+        .and_then(|period| lead_in.checked_add(period));
+    let mut base = lead_in - lead_in.rem_euclid(modulus);
+    loop {
+        for &(residue, _) in &congruences {
+            let step = base.checked_add(residue).ok_or_else(overflow_error)?;
+            if step < lead_in {
+                continue;
+            }
+            if walk_end.is_some_and(|walk_end| step >= walk_end) {
+                return Ok(None);
+            }
+            if remaining.iter().all(|ghost| ghost.is_end_at(step as u128)) {
+                return Ok(Some(step as u128));
+            }
+        }
+        base = base.checked_add(modulus).ok_or_else(overflow_error)?;
+    }
+}
@@ -0,0 +1,85 @@
+use super::{compute_instruction_results, Instruction, Network, NodeInstructionResult};
+
+// Binary lifting over instruction passes: `levels[k][node]` is the result of 2^k full passes
+// starting at `node`, with `first_end` counted in steps from the start of those passes
+#[derive(Debug)]
+pub struct JumpTable<'a> {
+    instructions: &'a [Instruction],
+    is_end: &'a [bool],
+    network: &'a Network,
+    levels: Vec<Vec<NodeInstructionResult>>,
+}
+
+impl<'a> JumpTable<'a> {
+    // Levels stop once 2^k passes no longer fit in a u64 step count
+    pub fn new(instructions: &'a [Instruction], is_end: &'a [bool], network: &'a Network) -> Self {
+        let instruction_length = instructions.len() as u64;
+        let mut levels = vec![compute_instruction_results(is_end, instructions, network)];
+        let mut level_steps = instruction_length;
+
+        while let Some(next_level_steps) = level_steps.checked_mul(2) {
+            let level = levels.last().unwrap();
+            let next_level = level
+                .iter()
+                .map(|first| {
+                    let second = level[first.result_node as usize];
+                    NodeInstructionResult {
+                        result_node: second.result_node,
+                        first_end: first
+                            .first_end
+                            .or(second.first_end.map(|step| level_steps + step)),
+                    }
+                })
+                .collect();
+            levels.push(next_level);
+            level_steps = next_level_steps;
+        }
+
+        JumpTable {
+            instructions,
+            is_end,
+            network,
+            levels,
+        }
+    }
+
+    // Jumps whole passes with the table, then single steps through the last partial pass.
+    // Returns the walker's node and the first end step seen, if any.
+    fn walk(&self, start_node: u32, steps: u64) -> (u32, Option<u64>) {
+        let instruction_length = self.instructions.len() as u64;
+        let passes = steps / instruction_length;
+        let mut current_node = start_node;
+        let mut steps_taken = 0u64;
+        let mut first_end = None;
+
+        for (k, level) in self.levels.iter().enumerate().rev() {
+            if passes & (1 << k) == 0 {
+                continue;
+            }
+            let jump = level[current_node as usize];
+            if first_end.is_none() {
+                first_end = jump.first_end.map(|step| steps_taken + step);
+            }
+            current_node = jump.result_node;
+            steps_taken += instruction_length << k;
+        }
+
+        for &instruction in &self.instructions[..(steps % instruction_length) as usize] {
+            current_node = self.network.step(current_node, instruction);
+            steps_taken += 1;
+            if first_end.is_none() && self.is_end[current_node as usize] {
+                first_end = Some(steps_taken);
+            }
+        }
+        (current_node, first_end)
+    }
+
+    pub fn position_after(&self, start_node: u32, steps: u64) -> u32 {
+        self.walk(start_node, steps).0
+    }
+
+    // The first of steps 1..=`steps` that lands on an end node
+    pub fn first_end_within(&self, start_node: u32, steps: u64) -> Option<u64> {
+        self.walk(start_node, steps).1
+    }
+}
@@ -1,185 +1,359 @@
-use regex::Regex;
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-};
+mod ghost_walk;
+mod jump_table;
+mod network_diagnostics;
 
-#[derive(Debug)]
-struct Node {
-    label: String,
-    left: String,
-    right: String,
+use ghost_walk::GhostCycle;
+use jump_table::JumpTable;
+use std::{collections::HashMap, env, fmt, fs};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    Left,
+    Right,
 }
 
+// Node labels are interned to dense ids, with each node's left and right ids in flat arrays
 #[derive(Debug)]
-enum NodeInstructionResult {
-    ResultNode(String),
-    Success(u64),
+struct Network {
+    labels: Vec<String>,
+    ids: HashMap<String, u32>,
+    left: Vec<u32>,
+    right: Vec<u32>,
 }
 
-fn compute_instruction_result<F: Fn(&str) -> bool>(
-    start_node: &str,
-    end_condition: F,
-    instructions: &str,
-    network: &HashMap<String, Node>,
-) -> NodeInstructionResult {
-    let mut current_node = network
-        .get(start_node)
-        .expect("Start node not found in network: {start_node}");
-    for (c, index) in instructions.chars().into_iter().zip(1..) {
-        let new_node = match c {
-            'L' => network
-                .get(&current_node.left)
-                .expect("Unable to follow {current_node} left"),
-            'R' => network
-                .get(&current_node.right)
-                .expect("Unable to follow {current_node} right"),
-            x => {
-                panic!("Unable to read instruction: {x}");
-            }
-        };
+impl Network {
+    fn len(&self) -> usize {
+        self.labels.len()
+    }
 
-        if end_condition(&new_node.label) {
-            return NodeInstructionResult::Success(index);
-        } else {
-            current_node = new_node;
+    fn id(&self, label: &str) -> Option<u32> {
+        self.ids.get(label).copied()
+    }
+
+    fn label(&self, id: u32) -> &str {
+        &self.labels[id as usize]
+    }
+
+    fn step(&self, id: u32, instruction: Instruction) -> u32 {
+        match instruction {
+            Instruction::Left => self.left[id as usize],
+            Instruction::Right => self.right[id as usize],
         }
     }
 
-    NodeInstructionResult::ResultNode(current_node.label.clone())
+    // One flag per node id, so the step loops never look at labels
+    fn mark(&self, predicate: impl Fn(&str) -> bool) -> Vec<bool> {
+        self.labels.iter().map(|label| predicate(label)).collect()
+    }
 }
 
-fn part_one(instructions: &str, network: &HashMap<String, Node>) -> u64 {
-    // First, compute each node's instruction result,
-    // ie the result of following the given instructions starting at that node
-    let node_instruction_results = network.keys().fold(HashMap::new(), |mut map, node| {
-        map.insert(
-            node,
-            compute_instruction_result(node, |s| s == "ZZZ", instructions, network),
-        );
-        map
-    });
-    // println!("{node_instruction_results:?}");
+// Where one full pass of the instructions leaves the walker, and the first step of the pass
+// (counting from 1) that lands on an end node
+#[derive(Debug, Clone, Copy)]
+struct NodeInstructionResult {
+    result_node: u32,
+    first_end: Option<u64>,
+}
 
-    let instruction_length = instructions.len() as u64;
-    let mut steps = 0u64;
-    let mut current_node = network.get("AAA").expect("AAA node not found");
+fn compute_instruction_result(
+    start_node: u32,
+    is_end: &[bool],
+    instructions: &[Instruction],
+    network: &Network,
+) -> NodeInstructionResult {
+    let mut current_node = start_node;
+    let mut first_end = None;
+    for (&instruction, index) in instructions.iter().zip(1..) {
+        current_node = network.step(current_node, instruction);
+        if first_end.is_none() && is_end[current_node as usize] {
+            first_end = Some(index);
+        }
+    }
 
-    loop {
-        match node_instruction_results.get(&current_node.label) {
-            Some(NodeInstructionResult::Success(step_count)) => {
-                break steps + step_count;
-            }
-            Some(NodeInstructionResult::ResultNode(node)) => {
-                steps += instruction_length;
-                current_node = network
-                    .get(node)
-                    .expect("Unable to find node in network: {node}");
-            }
-            None => {
-                panic!(
-                    "Could not find note in instruction results: {}",
-                    current_node.label
-                );
-            }
+    NodeInstructionResult {
+        result_node: current_node,
+        first_end,
+    }
+}
+
+// The result of following the given instructions from each node, indexed by node id
+fn compute_instruction_results(
+    is_end: &[bool],
+    instructions: &[Instruction],
+    network: &Network,
+) -> Vec<NodeInstructionResult> {
+    (0..network.len() as u32)
+        .map(|node| compute_instruction_result(node, is_end, instructions, network))
+        .collect()
+}
+
+// Whole instruction passes are skipped using the precomputed results; a walk that never
+// reaches an end node gives None
+fn steps_to_end(
+    start_node: u32,
+    node_instruction_results: &[NodeInstructionResult],
+    instruction_length: u64,
+) -> Option<u64> {
+    let mut steps = 0u64;
+    let mut current_node = start_node;
+    // A walk longer than one pass per node has repeated a pass start, so it loops forever
+    for _ in 0..=node_instruction_results.len() {
+        let result = node_instruction_results[current_node as usize];
+        if let Some(step_count) = result.first_end {
+            return Some(steps + step_count);
         }
+        steps += instruction_length;
+        current_node = result.result_node;
     }
+    None
 }
 
-fn part_two(instructions: &str, network: &HashMap<String, Node>) -> u64 {
-    // First, compute each node's instruction result,
-    // ie the result of following the given instructions starting at that node
-    let node_instruction_results = network.keys().fold(HashMap::new(), |mut map, node| {
-        map.insert(
-            node,
-            compute_instruction_result(node, |s| s.ends_with("Z"), instructions, network),
-        );
-        map
-    });
-    // println!("{node_instruction_results:?}");
-
-    let instruction_length = instructions.len() as u64;
-    let start_nodes = network
-        .keys()
-        .filter(|s| s.ends_with("A"))
-        .map(|s| network.get(s).ok_or("Unable to find node: {s}"))
-        .collect::<Result<Vec<_>, _>>()
-        .expect("Could not parse starting nodes");
-
-    start_nodes.into_iter().map(|node| {
-        let mut steps = 0u64;
-        let mut current_node = node;
-        loop {
-            match node_instruction_results.get(&current_node.label) {
-                Some(NodeInstructionResult::Success(step_count)) => {
-                    break steps + step_count;
-                },
-                Some(NodeInstructionResult::ResultNode(result_node)) => {
-                    steps += instruction_length;
-                    current_node = network
-                        .get(result_node)
-                        .expect("Unable to find node in network: {result_node}");
-                },
+fn part_one(instructions: &[Instruction], network: &Network) -> Option<u64> {
+    let is_end = network.mark(|label| label == "ZZZ");
+    let node_instruction_results = compute_instruction_results(&is_end, instructions, network);
+    steps_to_end(
+        network.id("AAA")?,
+        &node_instruction_results,
+        instructions.len() as u64,
+    )
+}
+
+fn start_nodes(network: &Network) -> Vec<u32> {
+    (0..network.len() as u32)
+        .filter(|&node| network.label(node).ends_with('A'))
+        .collect()
+}
+
+fn part_two(ghost_cycles: &[GhostCycle]) -> Result<Option<u128>, String> {
+    ghost_walk::first_common_end(ghost_cycles)
+}
+
+// A node definition is `<label> = (<left>, <right>)`; labels are any text without whitespace,
+// commas or parentheses
+fn parse_node_line(line: &str) -> Option<(&str, &str, &str)> {
+    let (label, children) = line.split_once('=')?;
+    let (left, right) = children
+        .trim()
+        .strip_prefix('(')?
+        .strip_suffix(')')?
+        .split_once(',')?;
+    let is_label = |s: &str| {
+        !s.is_empty()
+            && !s
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, ',' | '(' | ')' | '='))
+    };
+    let (label, left, right) = (label.trim(), left.trim(), right.trim());
+    (is_label(label) && is_label(left) && is_label(right)).then_some((label, left, right))
+}
+
+// A left or right link to a label that has no definition line
+#[derive(Debug)]
+struct DanglingLink {
+    line_number: usize,
+    label: String,
+    instruction: Instruction,
+    missing: String,
+}
+
+impl fmt::Display for DanglingLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.instruction {
+            Instruction::Left => "left",
+            Instruction::Right => "right",
+        };
+        write!(
+            f,
+            "line {}: node {} links {direction} to undefined node {}",
+            self.line_number, self.label, self.missing
+        )
+    }
+}
+
+type ParsedFile = (Vec<Instruction>, Network, Vec<DanglingLink>);
+
+fn parse_file_contents(file_contents: &str) -> Result<ParsedFile, Box<dyn std::error::Error>> {
+    let mut lines = file_contents.lines().zip(1..);
+    let instructions = lines
+        .next()
+        .ok_or("No instructions")?
+        .0
+        .trim()
+        .chars()
+        .map(|c| match c {
+            'L' => Ok(Instruction::Left),
+            'R' => Ok(Instruction::Right),
+            x => Err(format!("line 1: unable to read instruction: {x}")),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if instructions.is_empty() {
+        return Err("No instructions".into());
+    }
+
+    let node_lines = lines
+        .filter(|(line, _)| !line.trim().is_empty())
+        .map(|(line, line_number)| {
+            parse_node_line(line)
+                .map(|node| (node, line_number))
+                .ok_or(format!("line {line_number}: could not parse node: {line}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Defined labels take the first ids, in definition order
+    let mut ids = HashMap::new();
+    let mut labels = Vec::new();
+    for &((label, _, _), line_number) in &node_lines {
+        if ids.insert(label.to_owned(), labels.len() as u32).is_some() {
+            return Err(format!("line {line_number}: node {label} defined twice").into());
+        }
+        labels.push(label.to_owned());
+    }
+
+    // Undefined children become placeholder nodes that link to themselves, so the network can
+    // still be inspected; callers decide whether dangling links are an error
+    let mut left = Vec::with_capacity(labels.len());
+    let mut right = Vec::with_capacity(labels.len());
+    let mut dangling_links = Vec::new();
+    for &((label, left_label, right_label), line_number) in &node_lines {
+        for (instruction, child) in [
+            (Instruction::Left, left_label),
+            (Instruction::Right, right_label),
+        ] {
+            let child_id = match ids.get(child) {
+                Some(&child_id) => child_id,
                 None => {
-                    panic!("Could not find node in instruction results: {}", current_node.label);
+                    dangling_links.push(DanglingLink {
+                        line_number,
+                        label: label.to_owned(),
+                        instruction,
+                        missing: child.to_owned(),
+                    });
+                    ids.insert(child.to_owned(), labels.len() as u32);
+                    labels.push(child.to_owned());
+                    labels.len() as u32 - 1
                 }
+            };
+            match instruction {
+                Instruction::Left => left.push(child_id),
+                Instruction::Right => right.push(child_id),
             }
         }
-    }).reduce(|lcm, n| num::integer::lcm(lcm, n)).expect("Empty starting node set")
-}
-
-fn parse_file_contents(
-    file_contents: &str,
-) -> Result<(&str, HashMap<String, Node>), Box<dyn std::error::Error>> {
-    let mut lines = file_contents.lines();
-    let instructions = lines.next().ok_or("No instructions")?;
-    lines.next();
-
-    let network_re = Regex::new(r"([0-9A-Z]{3}) = \(([0-9A-Z]{3}), ([0-9A-Z]{3})\)").unwrap();
-    let network = lines
-        .map(|line| {
-            let network_capture = network_re
-                .captures(line)
-                .ok_or("Could not parse network definion")?;
-            if let (Some(label), Some(left), Some(right)) = (
-                network_capture.get(1),
-                network_capture.get(2),
-                network_capture.get(3),
-            ) {
-                Ok((
-                    label.as_str().to_owned(),
-                    Node {
-                        label: label.as_str().to_owned(),
-                        left: left.as_str().to_owned(),
-                        right: right.as_str().to_owned(),
-                    },
-                ))
-            } else {
-                Err("Could not parse node: {line}")
-            }
-        })
-        .collect::<Result<HashMap<_, _>, _>>()?;
+    }
+    for placeholder in left.len() as u32..labels.len() as u32 {
+        left.push(placeholder);
+        right.push(placeholder);
+    }
 
-    Ok((instructions, network))
+    Ok((
+        instructions,
+        Network {
+            labels,
+            ids,
+            left,
+            right,
+        },
+        dangling_links,
+    ))
 }
 
 fn main() {
     let file_path = "input.txt";
     let file_contents = fs::read_to_string(file_path).expect("Unable to read file: {file_path}");
 
-    let (instructions, network) = match parse_file_contents(&file_contents) {
+    let (instructions, network, dangling_links) = match parse_file_contents(&file_contents) {
         Ok(result) => result,
         Err(error) => {
             println!("Unable to parse file contents: {error}");
             return;
         }
     };
+
+    // `day08 --diagnose` reports on the network's shape and `day08 --dot` prints it for Graphviz;
+    // both work even when some links are dangling
+    match env::args().nth(1).as_deref() {
+        Some("--diagnose") => {
+            network_diagnostics::print_report(&instructions, &network, &dangling_links);
+            return;
+        }
+        Some("--dot") => {
+            print!("{}", network_diagnostics::to_dot(&network, &dangling_links));
+            return;
+        }
+        _ => {}
+    }
+    if let Some(dangling_link) = dangling_links.first() {
+        println!("Unable to parse file contents: {dangling_link}");
+        return;
+    }
     // println!("{instructions:?}");
     // println!("{network:?}");
 
-    // let part_one_step_count = part_one(instructions, &network);
-    // println!("Part One -- Step Count: {part_one_step_count}");
+    // `day08 --after=<steps> [start]` and `day08 --within=<steps> [start]` answer single walker
+    // queries for huge step counts, with end nodes ending in Z
+    let query = env::args().nth(1).and_then(|arg| {
+        arg.strip_prefix("--after=")
+            .map(|steps| (false, steps.to_string()))
+            .or(arg
+                .strip_prefix("--within=")
+                .map(|steps| (true, steps.to_string())))
+    });
+    if let Some((within, steps)) = query {
+        let start_label = env::args().nth(2).unwrap_or(String::from("AAA"));
+        let (Ok(steps), Some(start_node)) = (steps.parse::<u64>(), network.id(&start_label)) else {
+            println!("Expected a step count and a start node in the network");
+            return;
+        };
+        let is_end = network.mark(|label| label.ends_with('Z'));
+        let jump_table = JumpTable::new(&instructions, &is_end, &network);
+        if within {
+            match jump_table.first_end_within(start_node, steps) {
+                Some(step) => println!("From {start_label}, first end node at step {step}"),
+                None => println!("From {start_label}, no end node within {steps} steps"),
+            }
+        } else {
+            let node = jump_table.position_after(start_node, steps);
+            println!(
+                "From {start_label}, after {steps} steps: {}",
+                network.label(node)
+            );
+        }
+        return;
+    }
+
+    match part_one(&instructions, &network) {
+        Some(part_one_step_count) => println!("Part One -- Step Count: {part_one_step_count}"),
+        None => println!("Part One -- No path from AAA to ZZZ"),
+    }
+
+    let start_nodes = start_nodes(&network);
+    if start_nodes.is_empty() {
+        println!("Part Two -- No start nodes ending in A");
+        return;
+    }
+    let is_end = network.mark(|label| label.ends_with('Z'));
+    let ghost_cycles = start_nodes
+        .iter()
+        .map(|&node| GhostCycle::find(node, &is_end, &instructions, &network))
+        .collect::<Vec<_>>();
 
-    let part_two_step_count = part_two(instructions, &network);
-    println!("Part Two -- Step Count: {part_two_step_count}");
+    // `day08 --cycles` shows how each start node's walk settles into a loop
+    if env::args().nth(1).as_deref() == Some("--cycles") {
+        for (&node, ghost_cycle) in start_nodes.iter().zip(&ghost_cycles) {
+            println!(
+                "{}: offset {}, cycle length {}, end node at steps {:?}",
+                network.label(node),
+                ghost_cycle.offset,
+                ghost_cycle.cycle_length,
+                ghost_cycle.end_hits
+            );
+        }
+    }
+
+    match part_two(&ghost_cycles) {
+        Ok(Some(part_two_step_count)) => {
+            println!("Part Two -- Step Count: {part_two_step_count}")
+        }
+        Ok(None) => println!("Part Two -- Step Count: never"),
+        Err(err) => println!("Part Two -- Unable to find step count: {err}"),
+    }
 }
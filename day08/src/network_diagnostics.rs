@@ -0,0 +1,262 @@
+use super::{start_nodes, DanglingLink, GhostCycle, Instruction, Network};
+use std::{cmp, collections::HashSet, fmt::Write};
+
+// Labels from dangling links are placeholder nodes rather than real ones
+fn placeholder_nodes(network: &Network, dangling_links: &[DanglingLink]) -> HashSet<u32> {
+    dangling_links
+        .iter()
+        .filter_map(|dangling_link| network.id(&dangling_link.missing))
+        .collect()
+}
+
+fn children(network: &Network, node: u32) -> [u32; 2] {
+    [
+        network.step(node, Instruction::Left),
+        network.step(node, Instruction::Right),
+    ]
+}
+
+// Nodes no start node can reach by any choice of instructions
+fn unreachable_nodes(network: &Network, start_nodes: &[u32]) -> Vec<u32> {
+    let mut reached = vec![false; network.len()];
+    let mut to_visit = start_nodes.to_vec();
+    while let Some(node) = to_visit.pop() {
+        if std::mem::replace(&mut reached[node as usize], true) {
+            continue;
+        }
+        to_visit.extend(children(network, node));
+    }
+    (0..network.len() as u32)
+        .filter(|&node| !reached[node as usize])
+        .collect()
+}
+
+// Tarjan's algorithm with an explicit stack of (node, next child to try), so long chains of
+// nodes can't overflow the call stack
+fn strongly_connected_components(network: &Network) -> Vec<Vec<u32>> {
+    let mut index = vec![u32::MAX; network.len()];
+    let mut lowlink = vec![0; network.len()];
+    let mut on_stack = vec![false; network.len()];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    for root in 0..network.len() as u32 {
+        if index[root as usize] != u32::MAX {
+            continue;
+        }
+        let mut call_stack = vec![(root, 0)];
+        index[root as usize] = next_index;
+        lowlink[root as usize] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root as usize] = true;
+
+        while let Some(&mut (node, ref mut child_index)) = call_stack.last_mut() {
+            if let Some(&child) = children(network, node).get(*child_index) {
+                *child_index += 1;
+                if index[child as usize] == u32::MAX {
+                    index[child as usize] = next_index;
+                    lowlink[child as usize] = next_index;
+                    next_index += 1;
+                    stack.push(child);
+                    on_stack[child as usize] = true;
+                    call_stack.push((child, 0));
+                } else if on_stack[child as usize] {
+                    lowlink[node as usize] =
+                        cmp::min(lowlink[node as usize], index[child as usize]);
+                }
+                continue;
+            }
+
+            call_stack.pop();
+            if let Some(&(parent, _)) = call_stack.last() {
+                lowlink[parent as usize] =
+                    cmp::min(lowlink[parent as usize], lowlink[node as usize]);
+            }
+            if lowlink[node as usize] == index[node as usize] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member as usize] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+// Distinct nodes the walker visits once it is inside its cycle, in visiting order
+fn cycle_nodes(
+    start_node: u32,
+    ghost_cycle: &GhostCycle,
+    instructions: &[Instruction],
+    network: &Network,
+) -> Vec<u32> {
+    let mut current_node = start_node;
+    let mut seen = vec![false; network.len()];
+    let mut nodes = Vec::new();
+    for step in 0..ghost_cycle.offset + ghost_cycle.cycle_length {
+        if step >= ghost_cycle.offset && !std::mem::replace(&mut seen[current_node as usize], true)
+        {
+            nodes.push(current_node);
+        }
+        current_node = network.step(
+            current_node,
+            instructions[step as usize % instructions.len()],
+        );
+    }
+    nodes
+}
+
+fn label_list(network: &Network, nodes: &[u32]) -> String {
+    const SHOWN_LABELS: usize = 10;
+    let mut list = nodes
+        .iter()
+        .take(SHOWN_LABELS)
+        .map(|&node| network.label(node))
+        .collect::<Vec<&str>>()
+        .join(", ");
+    if nodes.len() > SHOWN_LABELS {
+        list += &format!(", ... ({} more)", nodes.len() - SHOWN_LABELS);
+    }
+    list
+}
+
+pub fn print_report(
+    instructions: &[Instruction],
+    network: &Network,
+    dangling_links: &[DanglingLink],
+) {
+    let placeholders = placeholder_nodes(network, dangling_links);
+    println!(
+        "Nodes: {} defined, {} undefined",
+        network.len() - placeholders.len(),
+        placeholders.len()
+    );
+
+    println!("Dangling Links: {}", dangling_links.len());
+    for dangling_link in dangling_links {
+        println!("  {dangling_link}");
+    }
+
+    let start_nodes = start_nodes(network);
+    let unreachable = unreachable_nodes(network, &start_nodes);
+    println!("Unreachable From Start Nodes: {}", unreachable.len());
+    if !unreachable.is_empty() {
+        println!("  {}", label_list(network, &unreachable));
+    }
+
+    let components = strongly_connected_components(network);
+    let cyclic_components = components
+        .into_iter()
+        .filter(|component| {
+            component.len() > 1 || children(network, component[0]).contains(&component[0])
+        })
+        .filter(|component| !placeholders.contains(&component[0]))
+        .collect::<Vec<_>>();
+    println!(
+        "Strongly Connected Components With Cycles: {}",
+        cyclic_components.len()
+    );
+    for component in &cyclic_components {
+        let end_count = component
+            .iter()
+            .filter(|&&node| network.label(node).ends_with('Z'))
+            .count();
+        println!(
+            "  {} nodes, {end_count} end nodes: {}",
+            component.len(),
+            label_list(network, component)
+        );
+    }
+
+    println!("Start Node Cycles:");
+    let is_end = network.mark(|label| label.ends_with('Z'));
+    for start_node in start_nodes {
+        let ghost_cycle = GhostCycle::find(start_node, &is_end, instructions, network);
+        let nodes = cycle_nodes(start_node, &ghost_cycle, instructions, network);
+        let end_nodes = nodes
+            .iter()
+            .copied()
+            .filter(|&node| is_end[node as usize])
+            .collect::<Vec<u32>>();
+        println!(
+            "  {}: enters a {} step cycle after {} steps, through {} nodes",
+            network.label(start_node),
+            ghost_cycle.cycle_length,
+            ghost_cycle.offset,
+            nodes.len()
+        );
+        if let Some(&placeholder) = nodes.iter().find(|node| placeholders.contains(node)) {
+            println!(
+                "    follows a dangling link to undefined node {}",
+                network.label(placeholder)
+            );
+        } else if end_nodes.is_empty() {
+            println!("    no end nodes in cycle");
+        } else {
+            println!(
+                "    end nodes in cycle: {}",
+                label_list(network, &end_nodes)
+            );
+        }
+    }
+}
+
+// Graphviz source: start nodes green, end nodes red, undefined nodes dashed with no links out
+pub fn to_dot(network: &Network, dangling_links: &[DanglingLink]) -> String {
+    let placeholders = placeholder_nodes(network, dangling_links);
+    let quoted = |node: u32| {
+        let label = network.label(node);
+        format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+    };
+
+    let mut dot = String::from("digraph network {\n");
+    for node in 0..network.len() as u32 {
+        let label = network.label(node);
+        let style = if placeholders.contains(&node) {
+            " [style=dashed, color=red]"
+        } else if label.ends_with('A') {
+            " [style=filled, fillcolor=palegreen]"
+        } else if label.ends_with('Z') {
+            " [style=filled, fillcolor=lightcoral]"
+        } else {
+            ""
+        };
+        writeln!(dot, "    {}{style};", quoted(node)).unwrap();
+    }
+    for node in (0..network.len() as u32).filter(|node| !placeholders.contains(node)) {
+        let [left, right] = children(network, node);
+        if left == right {
+            writeln!(
+                dot,
+                "    {} -> {} [label=\"L,R\"];",
+                quoted(node),
+                quoted(left)
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                dot,
+                "    {} -> {} [label=\"L\"];",
+                quoted(node),
+                quoted(left)
+            )
+            .unwrap();
+            writeln!(
+                dot,
+                "    {} -> {} [label=\"R\"];",
+                quoted(node),
+                quoted(right)
+            )
+            .unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
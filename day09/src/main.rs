@@ -1,77 +1,239 @@
-use regex::Regex;
-use std::fs;
-
-fn compute_next_value(history: &Vec<i32>) -> i32 {
-    let mut successive_differences = Vec::new();
-    successive_differences.push(history.clone());
-    while !successive_differences
-        .last()
-        .unwrap()
-        .iter()
-        .all(|&x| x == 0)
-    {
-        successive_differences.push(
-            successive_differences
-                .last()
-                .unwrap()
-                .windows(2)
-                .map(|pair| pair[1] - pair[0])
-                .collect::<Vec<i32>>(),
-        );
+mod streaming_predictor;
+
+use std::{env, error, fmt, fs};
+use streaming_predictor::StreamingPredictor;
+
+#[derive(Debug)]
+enum ExtrapolationError {
+    EmptyHistory,
+    Overflow,
+}
+
+impl fmt::Display for ExtrapolationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtrapolationError::EmptyHistory => write!(f, "History has no values"),
+            ExtrapolationError::Overflow => write!(f, "Value does not fit in an i128"),
+        }
     }
-    successive_differences
-        .into_iter()
-        .fold(0, |acc, diff| acc + diff.last().unwrap_or(&0))
 }
 
-fn compute_new_first_value(history: &Vec<i32>) -> i32 {
-    let mut successive_differences = Vec::new();
-    successive_differences.push(history.clone());
-    while !successive_differences
-        .last()
-        .unwrap()
-        .iter()
-        .all(|&x| x == 0)
-    {
-        successive_differences.push(
-            successive_differences
-                .last()
-                .unwrap()
-                .windows(2)
-                .map(|pair| pair[1] - pair[0])
-                .collect::<Vec<i32>>(),
-        );
+impl error::Error for ExtrapolationError {}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a as i128
+}
+
+// Newton's forward form of the lowest degree polynomial through a history: the value at index
+// x is the sum of `leading_differences[j] * C(x, j)`, where `leading_differences[j]` is the first
+// entry of the j-th row of differences. This is exactly what repeated differencing extrapolates.
+#[derive(Debug)]
+struct NewtonPolynomial {
+    leading_differences: Vec<i128>,
+}
+
+impl NewtonPolynomial {
+    // Differences are taken in place, so only one row of the table is ever held
+    fn from_history(history: &[i64]) -> Result<Self, ExtrapolationError> {
+        if history.is_empty() {
+            return Err(ExtrapolationError::EmptyHistory);
+        }
+        let mut leading_differences = history.iter().map(|&v| v as i128).collect::<Vec<i128>>();
+        for row in 1..leading_differences.len() {
+            for i in (row..leading_differences.len()).rev() {
+                leading_differences[i] = leading_differences[i]
+                    .checked_sub(leading_differences[i - 1])
+                    .ok_or(ExtrapolationError::Overflow)?;
+            }
+        }
+        Ok(NewtonPolynomial {
+            leading_differences,
+        })
+    }
+
+    fn history_len(&self) -> usize {
+        self.leading_differences.len()
+    }
+
+    // Index of the last non-zero difference, or None for the zero polynomial
+    fn degree(&self) -> Option<usize> {
+        self.leading_differences
+            .iter()
+            .rposition(|&difference| difference != 0)
+    }
+
+    // Index 0 is the first value of the history; negative indices extrapolate backwards
+    fn value_at(&self, index: i128) -> Result<i128, ExtrapolationError> {
+        let Some(degree) = self.degree() else {
+            return Ok(0);
+        };
+        let mut value = 0i128;
+        // C(index, j) = C(index, j - 1) * (index - j + 1) / j. Dividing out their common factor
+        // with j first means the product only overflows when C(index, j) itself does.
+        let mut binomial = 1i128;
+        for (j, &difference) in self.leading_differences[..=degree].iter().enumerate() {
+            if j > 0 {
+                let j = j as i128;
+                let common = gcd(binomial, j);
+                binomial = (binomial / common)
+                    .checked_mul((index - (j - 1)) / (j / common))
+                    .ok_or(ExtrapolationError::Overflow)?;
+            }
+            value = difference
+                .checked_mul(binomial)
+                .and_then(|term| value.checked_add(term))
+                .ok_or(ExtrapolationError::Overflow)?;
+        }
+        Ok(value)
+    }
+
+    fn forward(&self, steps: i128) -> Result<i128, ExtrapolationError> {
+        let index = (self.history_len() as i128 - 1)
+            .checked_add(steps)
+            .ok_or(ExtrapolationError::Overflow)?;
+        self.value_at(index)
+    }
+
+    fn backward(&self, steps: i128) -> Result<i128, ExtrapolationError> {
+        self.value_at(steps.checked_neg().ok_or(ExtrapolationError::Overflow)?)
     }
-    successive_differences
-        .into_iter()
-        .rev()
-        .fold(0, |acc, diff| diff.first().unwrap_or(&0) - acc)
 }
 
-fn part_one(histories: &Vec<Vec<i32>>) -> i32 {
-    histories.iter().map(compute_next_value).sum()
+// Sum of one prediction per history, with the line of the first history that fails
+fn sum_predictions(
+    polynomials: &[NewtonPolynomial],
+    predict: impl Fn(&NewtonPolynomial) -> Result<i128, ExtrapolationError>,
+) -> Result<i128, String> {
+    polynomials
+        .iter()
+        .zip(1..)
+        .try_fold(0i128, |sum, (polynomial, line_number)| {
+            predict(polynomial)
+                .and_then(|value| sum.checked_add(value).ok_or(ExtrapolationError::Overflow))
+                .map_err(|err| format!("line {line_number}: {err}"))
+        })
+}
+
+fn part_one(polynomials: &[NewtonPolynomial]) -> Result<i128, String> {
+    sum_predictions(polynomials, |polynomial| polynomial.forward(1))
+}
+
+fn part_two(polynomials: &[NewtonPolynomial]) -> Result<i128, String> {
+    sum_predictions(polynomials, |polynomial| polynomial.backward(1))
 }
 
-fn part_two(histories: &Vec<Vec<i32>>) -> i32 {
-    histories.iter().map(compute_new_first_value).sum()
+// Feeds each line's values through a predictor one at a time instead of collecting histories
+fn print_streaming_report(file_contents: &str) {
+    let mut next_value_sum = Some(0i128);
+    let mut flagged_count = 0;
+    for (line, line_number) in file_contents.lines().zip(1..) {
+        let mut predictor = StreamingPredictor::default();
+        for v in line.split_whitespace() {
+            let pushed = v
+                .parse::<i64>()
+                .map_err(|err| err.to_string())
+                .and_then(|value| predictor.push(value).map_err(|err| err.to_string()));
+            if let Err(err) = pushed {
+                println!("Unable to parse file contents: line {line_number}: {err}");
+                return;
+            }
+        }
+
+        if !predictor.is_polynomial() {
+            flagged_count += 1;
+            println!(
+                "line {line_number}: degree {} from {} values, not polynomial within its length",
+                predictor.degree().unwrap_or(0),
+                predictor.len()
+            );
+        }
+        next_value_sum = match predictor.predict_next() {
+            Ok(next_value) => next_value_sum.and_then(|sum| sum.checked_add(next_value)),
+            Err(err) => {
+                println!("line {line_number}: {err}");
+                None
+            }
+        };
+    }
+
+    println!("Streaming -- Histories Not Polynomial Within Their Length: {flagged_count}");
+    match next_value_sum {
+        Some(sum) => println!("Streaming -- Sum of History Next Values: {sum}"),
+        None => println!("Streaming -- Unable to sum history next values"),
+    }
+}
+
+fn parse_file_contents(file_contents: &str) -> Result<Vec<NewtonPolynomial>, String> {
+    file_contents
+        .lines()
+        .zip(1..)
+        .map(|(line, line_number)| {
+            let history = line
+                .split_whitespace()
+                .map(|v| v.parse::<i64>())
+                .collect::<Result<Vec<i64>, _>>()
+                .map_err(|err| format!("line {line_number}: {err}"))?;
+            NewtonPolynomial::from_history(&history)
+                .map_err(|err| format!("line {line_number}: {err}"))
+        })
+        .collect()
 }
 
 fn main() {
     let file_path = "input.txt";
     let file_contents = fs::read_to_string(file_path).expect("Unable to read file: {file_path}");
 
-    let histories = file_contents
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|v| v.parse::<i32>().unwrap())
-                .collect::<Vec<i32>>()
-        })
-        .collect::<Vec<Vec<i32>>>();
+    // `day09 --streaming` predicts next values without holding whole histories, and flags any
+    // history whose differences never reach a row of zeros
+    if env::args().nth(1).as_deref() == Some("--streaming") {
+        print_streaming_report(&file_contents);
+        return;
+    }
+
+    let polynomials = match parse_file_contents(&file_contents) {
+        Ok(polynomials) => polynomials,
+        Err(err) => {
+            println!("Unable to parse file contents: {err}");
+            return;
+        }
+    };
 
-    let part_one_history_sum = part_one(&histories);
-    println!("Part One -- Sum of History Next Values: {part_one_history_sum}");
-    
-    let part_two_history_sum = part_two(&histories);
-    println!("Part Two -- Sum of History New First Values: {part_two_history_sum}");
+    match part_one(&polynomials) {
+        Ok(part_one_history_sum) => {
+            println!("Part One -- Sum of History Next Values: {part_one_history_sum}")
+        }
+        Err(err) => println!("Part One -- Unable to extrapolate: {err}"),
+    }
+
+    match part_two(&polynomials) {
+        Ok(part_two_history_sum) => {
+            println!("Part Two -- Sum of History New First Values: {part_two_history_sum}")
+        }
+        Err(err) => println!("Part Two -- Unable to extrapolate: {err}"),
+    }
+
+    // `day09 --forward=<k>` and `day09 --backward=<k>` sum the values k steps past either end
+    for arg in env::args().skip(1) {
+        let (direction, predict, steps): (_, fn(&NewtonPolynomial, i128) -> _, _) =
+            if let Some(steps) = arg.strip_prefix("--forward=") {
+                ("Forward", NewtonPolynomial::forward, steps)
+            } else if let Some(steps) = arg.strip_prefix("--backward=") {
+                ("Backward", NewtonPolynomial::backward, steps)
+            } else {
+                continue;
+            };
+        let Ok(steps) = steps.parse::<i128>() else {
+            println!("Unable to read step count: {steps}");
+            continue;
+        };
+        let sum = sum_predictions(&polynomials, |polynomial| predict(polynomial, steps));
+        match sum {
+            Ok(sum) => println!("{direction} {steps} Steps -- Sum of Values: {sum}"),
+            Err(err) => println!("{direction} {steps} Steps -- Unable to extrapolate: {err}"),
+        }
+    }
 }
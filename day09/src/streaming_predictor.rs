@@ -0,0 +1,54 @@
+use super::ExtrapolationError;
+
+// Takes a history one value at a time, keeping only the newest entry of each row of
+// differences: `diagonal[j]` is the j-th difference ending at the latest value. The next value is
+// the sum of the diagonal, as with the full difference table.
+#[derive(Debug, Default)]
+pub struct StreamingPredictor {
+    diagonal: Vec<i128>,
+}
+
+impl StreamingPredictor {
+    pub fn push(&mut self, value: i64) -> Result<(), ExtrapolationError> {
+        let mut difference = value as i128;
+        for entry in self.diagonal.iter_mut() {
+            let next_difference = difference
+                .checked_sub(*entry)
+                .ok_or(ExtrapolationError::Overflow)?;
+            *entry = difference;
+            difference = next_difference;
+        }
+        self.diagonal.push(difference);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagonal.len()
+    }
+
+    // Degree of the lowest degree polynomial through the values so far, or None if they are all
+    // zero. Every difference row past the degree is entirely zero.
+    pub fn degree(&self) -> Option<usize> {
+        self.diagonal.iter().rposition(|&entry| entry != 0)
+    }
+
+    // A history is only shown to be polynomial once a whole row of differences is zero, which
+    // needs the degree to be at most two less than the number of values. Otherwise the last row
+    // is a single non-zero value and the prediction is just the forced interpolation.
+    pub fn is_polynomial(&self) -> bool {
+        match self.degree() {
+            Some(degree) => degree + 2 <= self.len(),
+            None => true,
+        }
+    }
+
+    pub fn predict_next(&self) -> Result<i128, ExtrapolationError> {
+        if self.diagonal.is_empty() {
+            return Err(ExtrapolationError::EmptyHistory);
+        }
+        self.diagonal
+            .iter()
+            .try_fold(0i128, |sum, &entry| sum.checked_add(entry))
+            .ok_or(ExtrapolationError::Overflow)
+    }
+}